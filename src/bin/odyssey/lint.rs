@@ -0,0 +1,132 @@
+use crate::locate::locate;
+use crate::Format;
+use odyssey::{validate, Runner, RunnerErrorEnum, Severity};
+use serde::Serialize;
+use std::fs;
+use std::process::ExitCode;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Finding {
+    file: String,
+    severity: Severity,
+    code: &'static str,
+    message: String,
+    pointer: String,
+    line: Option<usize>,
+    column: Option<usize>,
+}
+
+/// Validates each quiz file and, when that finds no errors, builds it the way a runner would.
+/// Exits with 1 if any file has errors.
+pub fn run(args: &[String]) -> Result<ExitCode, String> {
+    let mut format = Format::Human;
+    let mut files = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => format = Format::parse(args.next())?,
+            _ => files.push(arg.clone()),
+        }
+    }
+
+    if files.is_empty() {
+        return Err("lint needs at least one quiz file".into());
+    }
+
+    let mut findings = Vec::new();
+
+    for file in files {
+        let source = fs::read_to_string(&file).map_err(|error| format!("{file}: {error}"))?;
+        findings.extend(lint(&file, &source));
+    }
+
+    let errors = findings
+        .iter()
+        .filter(|finding| finding.severity == Severity::Error)
+        .count();
+
+    match format {
+        Format::Human => {
+            for finding in findings.iter() {
+                let position = match (finding.line, finding.column) {
+                    (Some(line), Some(column)) => format!(":{line}:{column}"),
+                    _ => String::new(),
+                };
+                let severity = match finding.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                };
+
+                println!(
+                    "{}{position}: {severity}[{}]: {}",
+                    finding.file, finding.code, finding.message
+                );
+            }
+
+            println!("{errors} error(s), {} warning(s)", findings.len() - errors);
+        }
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&findings).map_err(|error| error.to_string())?
+        ),
+    }
+
+    Ok(match errors {
+        0 => ExitCode::SUCCESS,
+        _ => ExitCode::FAILURE,
+    })
+}
+
+fn lint(file: &str, source: &str) -> Vec<Finding> {
+    let mut findings: Vec<Finding> = validate(source)
+        .into_iter()
+        .map(|diagnostic| {
+            // Malformed documents carry serde's own position in the message instead.
+            let (line, column) = match diagnostic.code() {
+                "invalidJson" => (None, None),
+                _ => {
+                    let (line, column) = locate(source, diagnostic.pointer());
+                    (Some(line), Some(column))
+                }
+            };
+
+            Finding {
+                file: file.into(),
+                severity: diagnostic.severity().clone(),
+                code: diagnostic.code(),
+                message: diagnostic.message().clone(),
+                pointer: diagnostic.pointer().clone(),
+                line,
+                column,
+            }
+        })
+        .collect();
+
+    let has_errors = findings
+        .iter()
+        .any(|finding| finding.severity == Severity::Error);
+
+    if !has_errors {
+        if let Err(error) = Runner::new(source) {
+            let code = match error.error() {
+                RunnerErrorEnum::StoreError { source } => source.code(),
+                RunnerErrorEnum::StateError { source } => source.code(),
+                _ => error.code(),
+            };
+
+            findings.push(Finding {
+                file: file.into(),
+                severity: Severity::Error,
+                code,
+                message: error.to_string(),
+                pointer: String::new(),
+                line: None,
+                column: None,
+            });
+        }
+    }
+
+    findings
+}
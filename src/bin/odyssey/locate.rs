@@ -0,0 +1,175 @@
+/// Finds the 1-based line and column of the value a JSON pointer refers to. Pointers into members
+/// or elements that do not exist resolve to their closest existing parent.
+pub fn locate(source: &str, pointer: &str) -> (usize, usize) {
+    let mut scanner = Scanner {
+        bytes: source.as_bytes(),
+        index: 0,
+    };
+    scanner.skip_whitespace();
+
+    for segment in pointer.split('/').skip(1) {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        let start = scanner.index;
+
+        let found = match scanner.peek() {
+            Some(b'{') => scanner.enter_member(&segment),
+            Some(b'[') => segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| scanner.enter_element(index)),
+            _ => None,
+        };
+
+        if found.is_none() {
+            scanner.index = start;
+            break;
+        }
+    }
+
+    position(source, scanner.index)
+}
+
+fn position(source: &str, index: usize) -> (usize, usize) {
+    let before = &source[..index];
+    let line = before.matches('\n').count() + 1;
+    let column = match before.rfind('\n') {
+        Some(newline) => before[newline + 1..].chars().count() + 1,
+        None => before.chars().count() + 1,
+    };
+
+    (line, column)
+}
+
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    index: usize,
+}
+
+impl Scanner<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.index).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|byte| byte.is_ascii_whitespace()) {
+            self.index += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: u8) -> Option<()> {
+        self.skip_whitespace();
+
+        if self.peek()? != expected {
+            return None;
+        }
+
+        self.index += 1;
+        self.skip_whitespace();
+        Some(())
+    }
+
+    /// Moves from an object's `{` to the value of member `key`.
+    fn enter_member(&mut self, key: &str) -> Option<()> {
+        self.expect(b'{')?;
+
+        while self.peek()? == b'"' {
+            let start = self.index;
+            self.skip_string()?;
+            let member = serde_json::from_slice::<String>(&self.bytes[start..self.index]).ok()?;
+            self.expect(b':')?;
+
+            if member == key {
+                return Some(());
+            }
+
+            self.skip_value()?;
+            self.expect(b',')?;
+        }
+
+        None
+    }
+
+    /// Moves from an array's `[` to its element at `index`.
+    fn enter_element(&mut self, index: usize) -> Option<()> {
+        self.expect(b'[')?;
+
+        if self.peek()? == b']' {
+            return None;
+        }
+
+        for _ in 0..index {
+            self.skip_value()?;
+            self.expect(b',')?;
+        }
+
+        Some(())
+    }
+
+    fn skip_string(&mut self) -> Option<()> {
+        self.index += 1;
+
+        loop {
+            match self.peek()? {
+                b'\\' => self.index += 2,
+                b'"' => {
+                    self.index += 1;
+                    return Some(());
+                }
+                _ => self.index += 1,
+            }
+        }
+    }
+
+    fn skip_value(&mut self) -> Option<()> {
+        match self.peek()? {
+            b'"' => self.skip_string(),
+            b'{' | b'[' => {
+                let mut depth = 0;
+
+                loop {
+                    match self.peek()? {
+                        b'"' => {
+                            self.skip_string()?;
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => depth -= 1,
+                        _ => {}
+                    }
+
+                    self.index += 1;
+
+                    if depth == 0 {
+                        return Some(());
+                    }
+                }
+            }
+            _ => {
+                while self.peek().is_some_and(|byte| {
+                    !matches!(byte, b',' | b'}' | b']') && !byte.is_ascii_whitespace()
+                }) {
+                    self.index += 1;
+                }
+
+                Some(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_locates_json_pointers() {
+        let source = "{\n  \"uid\": \"a/b\",\n  \"sections\": [\n    { \"id\": 1, \"questions\": [] },\n    {\n      \"id\": 2\n    }\n  ]\n}";
+
+        assert_eq!(locate(source, ""), (1, 1));
+        assert_eq!(locate(source, "/uid"), (2, 10));
+        assert_eq!(locate(source, "/sections/0/questions"), (4, 29));
+        assert_eq!(locate(source, "/sections/1/id"), (6, 13));
+        assert_eq!(locate(source, "/sections/1/answers"), (5, 5));
+        assert_eq!(locate(source, "/sections/7"), (3, 15));
+    }
+}
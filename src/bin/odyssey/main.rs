@@ -0,0 +1,59 @@
+mod lint;
+mod locate;
+mod play;
+mod render;
+mod replay;
+
+use std::env;
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: odyssey <command> [options]
+
+commands:
+  lint [--format human|json] <quiz.json>...   check quiz definitions
+  replay [--format human|json] [--steps] [--until <generation>] <quiz.json> <events.json>
+                                              rebuild a quiz view from an event log
+  play [--seed <seed>] [--events <events.json>] <quiz.json>
+                                              answer a quiz in the terminal";
+
+/// How results are printed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Human,
+    Json,
+}
+
+impl Format {
+    fn parse(value: Option<&String>) -> Result<Self, String> {
+        match value.map(String::as_str) {
+            Some("human") => Ok(Format::Human),
+            Some("json") => Ok(Format::Json),
+            Some(other) => Err(format!("unknown format `{other}`")),
+            None => Err("--format needs a value".into()),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let result = match args.first().map(String::as_str) {
+        Some("lint") => lint::run(&args[1..]),
+        Some("replay") => replay::run(&args[1..]),
+        Some("play") => play::run(&args[1..]),
+        Some("-h" | "--help") => {
+            println!("{USAGE}");
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(command) => Err(format!("unknown command `{command}`\n\n{USAGE}")),
+        None => Err(USAGE.into()),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,356 @@
+use crate::render::{label, quiz_table, section_header};
+use odyssey::{AnswerViewStatus, QuestionMode, QuestionView, QuizViewStatus, Runner};
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::process::ExitCode;
+
+const HELP: &str = "commands:
+  <question id>   open a question and answer it
+  list            show all sections and questions
+  submit          submit the quiz and finish
+  quit            stop without submitting";
+
+/// Plays a quiz on stdin/stdout and writes the event log on exit, also when the terminal fails
+/// midway. By default the log goes next to the quiz file as `<quiz>.events.json`, which must not
+/// exist yet.
+pub fn run(args: &[String]) -> Result<ExitCode, String> {
+    let mut seed = None;
+    let mut events_file = None;
+    let mut files = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => {
+                let value = args.next().ok_or("--seed needs a value")?;
+                seed = Some(
+                    value
+                        .parse::<u64>()
+                        .map_err(|_| format!("invalid seed `{value}`"))?,
+                );
+            }
+            "--events" => events_file = Some(args.next().ok_or("--events needs a value")?.clone()),
+            _ => files.push(arg.clone()),
+        }
+    }
+
+    let [quiz_file] = files.as_slice() else {
+        return Err("play needs exactly one quiz file".into());
+    };
+
+    let input = fs::read_to_string(quiz_file).map_err(|error| format!("{quiz_file}: {error}"))?;
+    let runner = match seed {
+        Some(seed) => Runner::new_with_seed(&input, seed),
+        None => Runner::new(&input),
+    }
+    .map_err(|error| format!("{quiz_file}: {error}"))?;
+
+    let events_file = match events_file {
+        Some(events_file) => events_file,
+        None => {
+            let events_file = Path::new(quiz_file).with_extension("events.json");
+
+            if events_file.exists() {
+                return Err(format!(
+                    "{}: already exists, choose another file with --events",
+                    events_file.display()
+                ));
+            }
+
+            events_file.to_string_lossy().into_owned()
+        }
+    };
+
+    let mut player = Player {
+        runner,
+        input: io::stdin().lock(),
+        output: io::stdout(),
+    };
+    let played = player.play();
+
+    let event_log = serde_json::to_string_pretty(player.runner.event_log())
+        .map_err(|error| error.to_string())?;
+    fs::write(&events_file, event_log).map_err(|error| format!("{events_file}: {error}"))?;
+    println!("event log written to {events_file}");
+
+    played.map_err(|error| error.to_string())?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+struct Player<R: BufRead, W: Write> {
+    runner: Runner,
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> Player<R, W> {
+    fn play(&mut self) -> io::Result<()> {
+        if let Err(error) = self.runner.start() {
+            writeln!(self.output, "{error}")?;
+        }
+
+        let quiz = self.runner.quiz_view();
+        writeln!(self.output, "{}", quiz_table(&quiz))?;
+
+        if let Some(description) = quiz.description() {
+            writeln!(self.output, "\n{description}")?;
+        }
+
+        writeln!(self.output, "\n{HELP}")?;
+
+        while let Some(command) = self.prompt("\n> ")? {
+            match command.as_str() {
+                "" => {}
+                "list" => writeln!(self.output, "{}", quiz_table(&self.runner.quiz_view()))?,
+                "help" => writeln!(self.output, "{HELP}")?,
+                "quit" => break,
+                "submit" => match self.runner.submit() {
+                    Ok(quiz) => {
+                        writeln!(self.output, "{}", quiz_table(&quiz))?;
+                        break;
+                    }
+                    Err(error) => writeln!(self.output, "{error}")?,
+                },
+                command => match command.parse::<usize>() {
+                    Ok(question_id) => self.answer(question_id)?,
+                    Err(_) => writeln!(self.output, "unknown command `{command}`\n{HELP}")?,
+                },
+            }
+
+            if *self.runner.quiz_view().status() != QuizViewStatus::InProgress {
+                writeln!(
+                    self.output,
+                    "quiz is {}, type `submit` or `quit`",
+                    label(self.runner.quiz_view().status())
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn prompt(&mut self, prompt: &str) -> io::Result<Option<String>> {
+        write!(self.output, "{prompt}")?;
+        self.output.flush()?;
+
+        let mut line = String::new();
+
+        match self.input.read_line(&mut line)? {
+            0 => Ok(None),
+            _ => Ok(Some(line.trim().to_string())),
+        }
+    }
+
+    fn answer(&mut self, question_id: usize) -> io::Result<()> {
+        let question = match self.runner.question_view(question_id) {
+            Ok(question) => question,
+            Err(error) => return writeln!(self.output, "{error}"),
+        };
+
+        let quiz = self.runner.quiz_view();
+        let section = quiz.sections().iter().find(|section| {
+            section
+                .questions()
+                .iter()
+                .any(|question| *question.id() == question_id)
+        });
+
+        if let Some(section) = section {
+            writeln!(self.output, "\n{}", section_header(section))?;
+
+            if let Some(description) = section.description() {
+                writeln!(self.output, "{description}")?;
+            }
+        }
+
+        self.render(&question)?;
+
+        let result = match question.mode() {
+            QuestionMode::Select | QuestionMode::Boolean => {
+                let Some(line) =
+                    self.prompt("answer ids, separated by spaces (empty to go back): ")?
+                else {
+                    return Ok(());
+                };
+
+                if line.is_empty() {
+                    return Ok(());
+                }
+
+                let answer_ids = line
+                    .split([' ', ','])
+                    .filter(|id| !id.is_empty())
+                    .map(str::parse::<usize>)
+                    .collect::<Result<Vec<usize>, _>>();
+
+                match answer_ids {
+                    Ok(answer_ids) => self.runner.select_answers(question_id, answer_ids),
+                    Err(_) => return writeln!(self.output, "answer ids have to be numbers"),
+                }
+            }
+            QuestionMode::Essay => {
+                let Some(line) = self.prompt("answer (empty to go back): ")? else {
+                    return Ok(());
+                };
+
+                if line.is_empty() {
+                    return Ok(());
+                }
+
+                self.runner.input_answers(question_id, vec![line])
+            }
+            QuestionMode::Input => {
+                let Some(line) =
+                    self.prompt("answer, separate entries with `;` (empty to go back): ")?
+                else {
+                    return Ok(());
+                };
+
+                if line.is_empty() {
+                    return Ok(());
+                }
+
+                let inputs = line
+                    .split(';')
+                    .map(|input| input.trim().to_string())
+                    .collect();
+                self.runner.input_answers(question_id, inputs)
+            }
+            mode => {
+                return writeln!(
+                    self.output,
+                    "{} questions can not be answered in the terminal",
+                    label(mode)
+                )
+            }
+        };
+
+        match result {
+            Ok(question) => {
+                writeln!(self.output, "{}", label(question.status()))?;
+
+                if let Some(feedback) = question.feedback() {
+                    writeln!(self.output, "{feedback}")?;
+                }
+
+                Ok(())
+            }
+            Err(error) => writeln!(self.output, "{error}"),
+        }
+    }
+
+    fn render(&mut self, question: &QuestionView) -> io::Result<()> {
+        writeln!(
+            self.output,
+            "\nquestion {}{}  ({}, {})",
+            question.id(),
+            question
+                .title()
+                .as_ref()
+                .map(|title| format!(": {title}"))
+                .unwrap_or_default(),
+            label(question.mode()),
+            label(question.status()),
+        )?;
+        writeln!(self.output, "{}", question.content())?;
+
+        match question.mode() {
+            QuestionMode::Select | QuestionMode::Boolean => {
+                for answer in question.answers() {
+                    let selected = match answer.status() {
+                        AnswerViewStatus::Pending => ' ',
+                        _ => '*',
+                    };
+
+                    writeln!(
+                        self.output,
+                        " {selected}[{}] {}",
+                        answer.id().unwrap_or_default(),
+                        answer.content()
+                    )?;
+                }
+            }
+            _ if !question.answers().is_empty() => {
+                let given: Vec<&str> = question
+                    .answers()
+                    .iter()
+                    .map(|answer| answer.content().as_str())
+                    .collect();
+                writeln!(self.output, "current answer: {}", given.join("; "))?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn it_records_answers_given_in_the_terminal() {
+        let mut player = Player {
+            runner: Runner::new(include_str!("../../../tests/input/branching_quiz.json")).unwrap(),
+            input: Cursor::new("2\n1\n2\nsubmit\n"),
+            output: Vec::new(),
+        };
+        player.play().unwrap();
+
+        let output = String::from_utf8(player.output).unwrap();
+        assert!(output.contains("question 2 is not available yet"));
+        assert!(output.contains("  [2] No"));
+
+        let events = serde_json::to_value(player.runner.event_log()).unwrap()["events"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|record| record["event"].as_str().unwrap().to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(events, vec!["startQuiz", "selectAnswers", "submitQuiz"]);
+    }
+
+    #[test]
+    fn it_keeps_essay_answers_whole() {
+        let mut player = Player {
+            runner: Runner::new(include_str!("../../../tests/input/essay_quiz.json")).unwrap(),
+            input: Cursor::new(
+                "2
+Food; warmth
+quit
+",
+            ),
+            output: Vec::new(),
+        };
+        player.play().unwrap();
+
+        let events = serde_json::to_value(player.runner.event_log()).unwrap()["events"].clone();
+        assert_eq!(events[1]["inputs"], serde_json::json!(["Food; warmth"]));
+    }
+
+    #[test]
+    fn it_refuses_to_overwrite_an_event_log() {
+        let dir = std::env::temp_dir().join(format!("odyssey-play-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let quiz_file = dir.join("quiz.json");
+        let events_file = dir.join("quiz.events.json");
+        fs::write(
+            &quiz_file,
+            include_str!("../../../tests/input/essay_quiz.json"),
+        )
+        .unwrap();
+        fs::write(&events_file, "{}").unwrap();
+
+        let result = run(&[quiz_file.to_string_lossy().into_owned()]);
+        let events = fs::read_to_string(&events_file).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(events, "{}");
+
+        assert!(result.unwrap_err().contains("already exists"));
+    }
+}
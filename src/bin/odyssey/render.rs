@@ -0,0 +1,71 @@
+use odyssey::{QuestionView, QuizView, SectionView};
+use serde::Serialize;
+
+/// Serialized name of a status or mode, e.g. `answeredCorrectly`.
+pub fn label<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(name)) => name,
+        Ok(serde_json::Value::Object(object)) => object.keys().cloned().collect(),
+        Ok(other) => other.to_string(),
+        Err(_) => String::new(),
+    }
+}
+
+pub fn quiz_table(quiz: &QuizView) -> String {
+    let mut lines = vec![format!(
+        "{} (v{})  {}  score {}/{}  answered {}  correct {}  wrong {}",
+        quiz.title().as_ref().unwrap_or(quiz.uid()),
+        quiz.version(),
+        label(quiz.status()),
+        points(*quiz.score()),
+        quiz.max_score(),
+        quiz.answered_questions_count(),
+        quiz.correct_questions_count(),
+        quiz.wrong_questions_count(),
+    )];
+
+    for section in quiz.sections() {
+        lines.push(String::new());
+        lines.push(section_header(section));
+        lines.push(format!(
+            "  {:<6} {:<20} {:<12} {:>8}",
+            "id", "status", "availability", "score"
+        ));
+
+        for question in section.questions() {
+            lines.push(question_row(question));
+        }
+    }
+
+    lines.join("\n")
+}
+
+pub fn section_header(section: &SectionView) -> String {
+    format!(
+        "section {}{}  {}  score {}/{}",
+        section.id(),
+        section
+            .title()
+            .as_ref()
+            .map(|title| format!(" {title}"))
+            .unwrap_or_default(),
+        label(section.status()),
+        points(*section.score()),
+        section.max_score(),
+    )
+}
+
+pub fn question_row(question: &QuestionView) -> String {
+    format!(
+        "  {:<6} {:<20} {:<12} {:>8}",
+        question.id(),
+        label(question.status()),
+        label(question.availability()),
+        format!("{}/{}", points(*question.score()), question.points()),
+    )
+}
+
+/// Penalties can leave a score at negative zero, which reads oddly as `-0`.
+fn points(score: f64) -> f64 {
+    score + 0.0
+}
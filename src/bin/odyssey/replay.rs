@@ -0,0 +1,167 @@
+use crate::render::{question_row, quiz_table};
+use crate::Format;
+use odyssey::{QuestionView, QuizView, Runner};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::process::ExitCode;
+
+/// Questions whose view differs after an event.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Step {
+    generation: usize,
+    event: Value,
+    questions: Vec<QuestionView>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Replay {
+    steps: Vec<Step>,
+    view: QuizView,
+}
+
+/// Rebuilds the quiz from an event log and prints the resulting view. Views are taken with the
+/// clock frozen at the last replayed event, so remaining time reads as it did for the candidate.
+pub fn run(args: &[String]) -> Result<ExitCode, String> {
+    let mut format = Format::Human;
+    let mut steps = false;
+    let mut until = None;
+    let mut files = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => format = Format::parse(args.next())?,
+            "--steps" => steps = true,
+            "--until" => {
+                let generation = args.next().ok_or("--until needs a value")?;
+                until = Some(
+                    generation
+                        .parse::<usize>()
+                        .map_err(|_| format!("invalid generation `{generation}`"))?,
+                );
+            }
+            _ => files.push(arg.clone()),
+        }
+    }
+
+    let [quiz_file, event_log_file] = files.as_slice() else {
+        return Err("replay needs a quiz file and an event log file".into());
+    };
+
+    let read = |file: &String| fs::read_to_string(file).map_err(|error| format!("{file}: {error}"));
+    let runner = Runner::new_with_events(&read(quiz_file)?, &read(event_log_file)?)
+        .map_err(|error| format!("{event_log_file}: {error}"))?;
+
+    let records = runner.event_log().events();
+    let generation = until.unwrap_or(records.len());
+
+    if generation > records.len() {
+        return Err(format!(
+            "event log has only {} events, can not replay until {generation}",
+            records.len()
+        ));
+    }
+
+    let mut views = Vec::new();
+
+    for view in runner.snapshots().take(generation + 1) {
+        views.push(view.map_err(|error| error.to_string())?);
+    }
+
+    let steps = match steps {
+        true => views
+            .windows(2)
+            .zip(records.iter())
+            .enumerate()
+            .map(|(index, (pair, record))| Step {
+                generation: index + 1,
+                event: serde_json::to_value(record).unwrap_or_default(),
+                questions: changed_questions(&pair[0], &pair[1]),
+            })
+            .collect(),
+        false => Vec::new(),
+    };
+
+    let view = views.pop().ok_or("nothing to replay")?;
+
+    match format {
+        Format::Human => {
+            for step in steps.iter() {
+                println!("#{} {}", step.generation, step.event);
+
+                for question in step.questions.iter() {
+                    println!("{}", question_row(question));
+                }
+            }
+
+            if !steps.is_empty() {
+                println!();
+            }
+
+            println!("{}", quiz_table(&view));
+        }
+        Format::Json => {
+            let output = match steps.is_empty() {
+                true => serde_json::to_string_pretty(&view),
+                false => serde_json::to_string_pretty(&Replay { steps, view }),
+            };
+            println!("{}", output.map_err(|error| error.to_string())?);
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn changed_questions(before: &QuizView, after: &QuizView) -> Vec<QuestionView> {
+    let questions = |view: &QuizView| -> Vec<QuestionView> {
+        view.sections()
+            .iter()
+            .flat_map(|section| section.questions().iter().cloned())
+            .collect()
+    };
+    let before = questions(before);
+
+    questions(after)
+        .into_iter()
+        .filter(|question| {
+            let previous = before
+                .iter()
+                .find(|previous| previous.id() == question.id());
+
+            previous.map(serde_json::to_value).and_then(Result::ok)
+                != serde_json::to_value(question).ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_lists_questions_changed_by_each_event() {
+        let runner = Runner::new_with_events(
+            include_str!("../../../tests/input/open_exam_quiz.json"),
+            include_str!("../../../tests/input/open_exam_event_log.json"),
+        )
+        .unwrap();
+        let views: Vec<QuizView> = runner.snapshots().map(Result::unwrap).collect();
+
+        let changed: Vec<Vec<usize>> = views
+            .windows(2)
+            .map(|pair| {
+                changed_questions(&pair[0], &pair[1])
+                    .iter()
+                    .map(|question| *question.id())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            changed,
+            vec![vec![1], vec![2], vec![2], vec![2], vec![3], vec![4]]
+        );
+    }
+}
@@ -0,0 +1,34 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source of event timestamps, in milliseconds since the Unix epoch.
+pub trait Clock {
+    fn now(&self) -> u64;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or(0)
+    }
+}
+
+/// Clock standing still at a given time, as used when replaying a log.
+pub struct FixedClock {
+    now: u64,
+}
+
+impl FixedClock {
+    pub fn new(now: u64) -> Self {
+        Self { now }
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> u64 {
+        self.now
+    }
+}
@@ -6,14 +6,17 @@ use serde::{Deserialize, Serialize};
 pub struct EventLog {
     uid: String,
     version: usize,
-    events: Vec<Event>,
+    #[serde(default)]
+    seed: u64,
+    events: Vec<EventRecord>,
 }
 
 impl EventLog {
-    pub fn new(uid: String, version: usize, events: Vec<Event>) -> Self {
+    pub fn new(uid: String, version: usize, seed: u64, events: Vec<EventRecord>) -> Self {
         Self {
             uid,
             version,
+            seed,
             events,
         }
     }
@@ -22,19 +25,123 @@ impl EventLog {
         self.events.len()
     }
 
-    pub fn push(&mut self, event: Event) {
+    pub fn push(&mut self, event: EventRecord) {
         self.events.push(event);
     }
 
-    pub fn extract_events(self) -> Vec<Event> {
+    pub fn pop(&mut self) -> Option<EventRecord> {
+        self.events.pop()
+    }
+
+    /// Events still in effect once `Undo`/`Redo` markers are resolved, oldest first.
+    pub fn effective_events(&self) -> Vec<EventRecord> {
+        let mut effective_events = Vec::new();
+        let mut undone_events = Vec::new();
+
+        for record in self.events.iter() {
+            match record.event() {
+                Event::Undo => undone_events.extend(effective_events.pop()),
+                Event::Redo => effective_events.extend(undone_events.pop()),
+                _ => {
+                    undone_events.clear();
+                    effective_events.push(record.clone());
+                }
+            }
+        }
+
+        effective_events
+    }
+
+    pub fn extract_events(self) -> Vec<EventRecord> {
         self.events
     }
 }
 
+/// An event as logged, stamped with the time it happened. Logs written before timestamps were
+/// recorded replay without time limit checks.
+#[derive(Serialize, Deserialize, Debug, Clone, Getters)]
+#[serde(rename_all = "camelCase")]
+pub struct EventRecord {
+    timestamp: Option<u64>,
+    #[serde(flatten)]
+    event: Event,
+}
+
+impl EventRecord {
+    pub fn new(timestamp: Option<u64>, event: Event) -> Self {
+        Self { timestamp, event }
+    }
+}
+
+impl Event {
+    pub fn question_id(&self) -> Option<usize> {
+        match self {
+            Event::SelectAnswers { question_id, .. }
+            | Event::InputAnswers { question_id, .. }
+            | Event::OrderAnswers { question_id, .. }
+            | Event::MatchAnswers { question_id, .. }
+            | Event::FillBlanks { question_id, .. }
+            | Event::ClearAnswers { question_id }
+            | Event::RevealHint { question_id }
+            | Event::GradeQuestion { question_id, .. } => Some(*question_id),
+            Event::StartQuiz
+            | Event::SubmitQuiz
+            | Event::NewAttempt
+            | Event::Undo
+            | Event::Redo => None,
+        }
+    }
+
+    /// Handing in, retaking and grading happen once the candidate is done, so they are never
+    /// subject to time limits. Undo and redo markers are checked through the events they affect.
+    pub fn is_timed(&self) -> bool {
+        !matches!(
+            self,
+            Event::SubmitQuiz
+                | Event::NewAttempt
+                | Event::Undo
+                | Event::Redo
+                | Event::GradeQuestion { .. }
+        )
+    }
+
+    /// Events changing the answers of a single question.
+    pub fn is_answer(&self) -> bool {
+        matches!(
+            self,
+            Event::SelectAnswers { .. }
+                | Event::InputAnswers { .. }
+                | Event::OrderAnswers { .. }
+                | Event::MatchAnswers { .. }
+                | Event::FillBlanks { .. }
+                | Event::ClearAnswers { .. }
+        )
+    }
+
+    /// Everything but starting, submitting or restarting the quiz and the undo markers themselves
+    /// can be taken back.
+    pub fn is_undoable(&self) -> bool {
+        !matches!(
+            self,
+            Event::StartQuiz | Event::SubmitQuiz | Event::NewAttempt | Event::Undo | Event::Redo
+        )
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "event")]
 #[serde(rename_all = "camelCase")]
 pub enum Event {
+    /// Starts the quiz without answering a question.
+    StartQuiz,
+    /// Hands in the quiz, after which answers can no longer change.
+    SubmitQuiz,
+    /// Archives the finished attempt and starts the next one.
+    NewAttempt,
+    /// Takes back the latest event still in effect, unless it starts, submits or restarts the quiz.
+    Undo,
+    /// Restores the latest undone event.
+    Redo,
     #[serde(rename_all = "camelCase")]
     SelectAnswers {
         question_id: usize,
@@ -46,7 +153,51 @@ pub enum Event {
         inputs: Vec<String>,
     },
     #[serde(rename_all = "camelCase")]
+    OrderAnswers {
+        question_id: usize,
+        answer_ids: Vec<usize>,
+    },
+    #[serde(rename_all = "camelCase")]
+    MatchAnswers {
+        question_id: usize,
+        pairs: Vec<(usize, usize)>,
+    },
+    #[serde(rename_all = "camelCase")]
+    FillBlanks {
+        question_id: usize,
+        entries: Vec<BlankEntry>,
+    },
+    #[serde(rename_all = "camelCase")]
     ClearAnswers { question_id: usize },
+    #[serde(rename_all = "camelCase")]
+    RevealHint { question_id: usize },
+    #[serde(rename_all = "camelCase")]
+    GradeQuestion {
+        question_id: usize,
+        points: f64,
+        feedback: Option<String>,
+        grader: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+#[serde(rename_all = "camelCase")]
+pub enum BlankEntry {
+    #[serde(rename_all = "camelCase")]
+    Selection { blank_id: usize, answer_id: usize },
+    #[serde(rename_all = "camelCase")]
+    Input { blank_id: usize, content: String },
+}
+
+impl BlankEntry {
+    pub fn blank_id(&self) -> usize {
+        match self {
+            BlankEntry::Selection { blank_id, .. } | BlankEntry::Input { blank_id, .. } => {
+                *blank_id
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -15,6 +15,23 @@ pub enum QuestionStatusInput {
     Answered,
     AnsweredCorrectly,
     AnsweredWrongly,
+    AwaitingReview,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum FeedbackReveal {
+    #[default]
+    OnStatus,
+    OnQuizFinished,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Getters)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedbackInput {
+    correct: Option<String>,
+    wrong: Option<String>,
+    answered: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Getters)]
@@ -22,6 +39,8 @@ pub enum QuestionStatusInput {
 pub struct AnswerInput {
     id: usize,
     content: String,
+    points: Option<f64>,
+    feedback: Option<FeedbackInput>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -29,6 +48,59 @@ pub struct AnswerInput {
 pub enum QuestionMode {
     Select,
     Input,
+    Order,
+    Match,
+    Cloze,
+    Essay,
+    Boolean,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Getters)]
+#[serde(rename_all = "camelCase")]
+pub struct BooleanLabels {
+    true_label: String,
+    false_label: String,
+}
+
+impl Default for BooleanLabels {
+    fn default() -> Self {
+        Self {
+            true_label: "True".into(),
+            false_label: "False".into(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ScoringPolicy {
+    #[default]
+    AllOrNothing,
+    Proportional,
+    RightMinusWrong,
+}
+
+/// Which attempt results make up the final score when a quiz is retaken.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum AttemptScoring {
+    #[default]
+    Best,
+    Last,
+    Average,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Getters)]
+#[serde(rename_all = "camelCase")]
+pub struct NumericMatch {
+    value: Option<f64>,
+    tolerance: Option<f64>,
+    relative_tolerance: Option<f64>,
+    min: Option<f64>,
+    max: Option<f64>,
+    unit: Option<String>,
+    #[serde(default)]
+    require_unit: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -39,6 +111,87 @@ pub enum EntryMatch {
     Id { id: Vec<usize> },
     #[serde(rename_all = "camelCase")]
     Content { content: Vec<String> },
+    #[serde(rename_all = "camelCase")]
+    Pairs { pairs: Vec<(usize, usize)> },
+    #[serde(rename_all = "camelCase")]
+    Numeric { numeric: Vec<NumericMatch> },
+}
+
+/// Matches once the referenced question is answered. Every listed answer has to be selected and
+/// the question has to be in `status` when given.
+#[derive(Serialize, Deserialize, Debug, Clone, Getters)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchConditionInput {
+    question_id: usize,
+    answer_ids: Option<Vec<usize>>,
+    status: Option<QuestionStatusInput>,
+}
+
+/// Jumps forward to a question or section, skipping everything in between, and/or skips the
+/// listed questions and sections.
+#[derive(Serialize, Deserialize, Debug, Clone, Getters)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchActionInput {
+    go_to_question: Option<usize>,
+    go_to_section: Option<usize>,
+    #[serde(default)]
+    skip_questions: Vec<usize>,
+    #[serde(default)]
+    skip_sections: Vec<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Getters)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchRuleInput {
+    when: BranchConditionInput,
+    then: Option<BranchActionInput>,
+    #[serde(rename = "else")]
+    otherwise: Option<BranchActionInput>,
+}
+
+/// Draws `count` questions from a section. Only questions carrying at least one of `tags` and
+/// a difficulty within the given bounds are eligible.
+#[derive(Serialize, Deserialize, Debug, Clone, Getters)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolInput {
+    count: usize,
+    tags: Option<Vec<String>>,
+    min_difficulty: Option<usize>,
+    max_difficulty: Option<usize>,
+}
+
+impl PoolInput {
+    pub fn accepts(&self, question_tags: &[String], difficulty: Option<usize>) -> bool {
+        let tagged = match &self.tags {
+            Some(tags) => tags.iter().any(|tag| question_tags.contains(tag)),
+            None => true,
+        };
+
+        let within_difficulty = match (difficulty, self.min_difficulty, self.max_difficulty) {
+            (_, None, None) => true,
+            (None, _, _) => false,
+            (Some(difficulty), min, max) => {
+                min.is_none_or(|min| difficulty >= min) && max.is_none_or(|max| difficulty <= max)
+            }
+        };
+
+        tagged && within_difficulty
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Getters)]
+#[serde(rename_all = "camelCase")]
+pub struct HintInput {
+    content: String,
+    cost: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Getters)]
+#[serde(rename_all = "camelCase")]
+pub struct BlankInput {
+    id: usize,
+    correct_entry_match: Option<EntryMatch>,
+    answers: Option<Vec<AnswerInput>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Getters)]
@@ -48,14 +201,79 @@ pub struct QuestionInput {
     title: Option<String>,
     content: String,
     mode: QuestionMode,
+    feedback: Option<FeedbackInput>,
+    explanation: Option<String>,
+    hints: Option<Vec<HintInput>>,
+    #[serde(default)]
+    tags: Vec<String>,
+    difficulty: Option<usize>,
+    time_limit: Option<u64>,
+    #[serde(default)]
+    branches: Vec<BranchRuleInput>,
     #[serde(default)]
     optional: bool,
+    points: Option<f64>,
+    #[serde(default)]
+    scoring_policy: ScoringPolicy,
+    wrong_entry_penalty: Option<f64>,
     min_entries: Option<usize>,
     max_entries: Option<usize>,
     min_correct_entries: Option<usize>,
     max_wrong_entries: Option<usize>,
     correct_entry_match: Option<EntryMatch>,
+    correct: Option<bool>,
+    boolean_labels: Option<BooleanLabels>,
+    shuffle_answers: Option<bool>,
     answers: Option<Vec<AnswerInput>>,
+    match_answers: Option<Vec<AnswerInput>>,
+    blanks: Option<Vec<BlankInput>>,
+}
+
+impl QuestionInput {
+    pub fn max_points(&self) -> f64 {
+        max_points(
+            self.points,
+            self.answers.iter().flatten().map(|answer| answer.points),
+        )
+    }
+}
+
+/// A question is worth its own points if given, else the sum of its positive answer points,
+/// else a single point.
+pub fn max_points(points: Option<f64>, answer_points: impl Iterator<Item = Option<f64>>) -> f64 {
+    let answer_points: Vec<f64> = answer_points.flatten().collect();
+
+    match points {
+        Some(points) => points,
+        None if !answer_points.is_empty() => answer_points
+            .into_iter()
+            .filter(|points| *points > 0.0)
+            .sum(),
+        None => 1.0,
+    }
+}
+
+/// Completion and pass criteria, shared by quizzes and their sections.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Getters)]
+#[serde(rename_all = "camelCase")]
+pub struct ThresholdsInput {
+    min_answered_questions: Option<usize>,
+    max_answered_questions: Option<usize>,
+    min_correct_questions: Option<usize>,
+    max_wrong_questions: Option<usize>,
+    min_score: Option<f64>,
+    pass_score: Option<f64>,
+}
+
+impl ThresholdsInput {
+    pub fn is_empty(&self) -> bool {
+        self.min_answered_questions.is_none()
+            && self.max_answered_questions.is_none()
+            && self.min_correct_questions.is_none()
+            && self.max_wrong_questions.is_none()
+            && self.min_score.is_none()
+            && self.pass_score.is_none()
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Getters)]
@@ -64,6 +282,15 @@ pub struct SectionInput {
     id: usize,
     title: Option<String>,
     description: Option<String>,
+    mode: Option<QuizMode>,
+    #[serde(flatten)]
+    thresholds: ThresholdsInput,
+    time_limit: Option<u64>,
+    shuffle_questions: Option<bool>,
+    shuffle_answers: Option<bool>,
+    pool: Option<PoolInput>,
+    #[serde(default)]
+    branches: Vec<BranchRuleInput>,
     questions: Vec<QuestionInput>,
 }
 
@@ -76,10 +303,22 @@ pub struct QuizInput {
     description: Option<String>,
     mode: QuizMode,
     block_answer_updates_for: Option<Vec<QuestionStatusInput>>,
-    min_answered_questions: Option<usize>,
-    max_answered_questions: Option<usize>,
-    min_correct_questions: Option<usize>,
-    max_wrong_questions: Option<usize>,
+    #[serde(flatten)]
+    thresholds: ThresholdsInput,
+    time_limit: Option<u64>,
+    max_attempts: Option<usize>,
+    #[serde(default)]
+    attempt_scoring: AttemptScoring,
+    wrong_entry_penalty: Option<f64>,
+    boolean_labels: Option<BooleanLabels>,
+    #[serde(default)]
+    feedback_reveal: FeedbackReveal,
+    #[serde(default)]
+    shuffle_sections: bool,
+    #[serde(default)]
+    shuffle_questions: bool,
+    #[serde(default)]
+    shuffle_answers: bool,
     sections: Vec<SectionInput>,
 }
 
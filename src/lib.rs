@@ -1,11 +1,22 @@
+mod clock;
 mod event_log;
 mod input;
+mod random;
 mod runner;
 mod state;
 mod store;
+mod validation;
 mod view;
 mod view_cache;
 
-pub use input::QuizMode;
-pub use runner::Runner;
-pub use view::{AnswerViewStatus, QuestionViewStatus};
+pub use clock::{Clock, FixedClock, SystemClock};
+pub use event_log::BlankEntry;
+pub use input::{QuestionMode, QuizMode};
+pub use runner::{Runner, RunnerError, RunnerErrorEnum, Snapshots, UndoMode};
+pub use state::{StateError, StateErrorEnum};
+pub use store::{StoreError, StoreErrorEnum, BOOLEAN_FALSE_ANSWER_ID, BOOLEAN_TRUE_ANSWER_ID};
+pub use validation::{validate, Diagnostic, Severity};
+pub use view::{
+    AnswerView, AnswerViewStatus, AttemptView, BlankView, QuestionAvailabilityView, QuestionView,
+    QuestionViewStatus, QuizView, QuizViewStatus, ReviewView, SectionView, SectionViewStatus,
+};
@@ -0,0 +1,57 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// Small deterministic generator (SplitMix64). Identical seeds always yield identical sequences,
+/// which lets a replayed event log reproduce the exact layout a candidate saw.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Fresh seed for a new run, drawn from the per-process random hasher keys.
+    pub fn generate_seed() -> u64 {
+        RandomState::new().build_hasher().finish()
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform index in `0..bound`.
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            items.swap(index, self.below(index + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_shuffles_deterministically_per_seed() {
+        let mut first: Vec<usize> = (0..20).collect();
+        let mut second: Vec<usize> = (0..20).collect();
+        Rng::new(42).shuffle(&mut first);
+        Rng::new(42).shuffle(&mut second);
+
+        assert_eq!(first, second);
+        assert_ne!(first, (0..20).collect::<Vec<usize>>());
+
+        first.sort_unstable();
+        assert_eq!(first, (0..20).collect::<Vec<usize>>());
+    }
+}
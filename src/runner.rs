@@ -1,23 +1,96 @@
-use crate::event_log::{Event, EventLog};
+use crate::clock::{Clock, FixedClock, SystemClock};
+use crate::event_log::{BlankEntry, Event, EventLog, EventRecord};
 use crate::input::QuizInput;
+use crate::random::Rng;
 use crate::state::{QuizState, StateError};
-use crate::store::{QuizStore, StoreError};
+use crate::store::{ErrorBody, QuizStore, StoreError};
 use crate::view::{QuestionView, QuizView, SectionView};
 use crate::view_cache::ViewCache;
+use derive_getters::Getters;
+use serde::Serialize;
 use serde_json::Error as JsonError;
+use std::error::Error;
+use std::fmt;
 
 type RunnerResult<T> = Result<T, RunnerError>;
 
-#[derive(Debug)]
+#[derive(Debug, Getters)]
 pub struct RunnerError {
-    error: RunnerErrorEnum,
+    pub(crate) error: RunnerErrorEnum,
 }
 
-#[derive(Debug)]
-enum RunnerErrorEnum {
-    InputError { source: JsonError },
-    StoreError { source: StoreError },
-    StateError { source: StateError },
+#[derive(Debug, Serialize)]
+#[serde(
+    tag = "code",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+#[non_exhaustive]
+pub enum RunnerErrorEnum {
+    InputError {
+        #[serde(skip)]
+        source: JsonError,
+    },
+    StoreError {
+        source: StoreError,
+    },
+    StateError {
+        source: StateError,
+    },
+    NothingToUndo,
+    NothingToRedo,
+    GenerationOutOfRange {
+        generation: usize,
+    },
+}
+
+impl RunnerError {
+    /// Stable machine-readable code, also used as `code` in the serialized form. Store and state
+    /// errors keep their own code under `source`.
+    pub fn code(&self) -> &'static str {
+        match self.error {
+            RunnerErrorEnum::InputError { .. } => "inputError",
+            RunnerErrorEnum::StoreError { .. } => "storeError",
+            RunnerErrorEnum::StateError { .. } => "stateError",
+            RunnerErrorEnum::NothingToUndo => "nothingToUndo",
+            RunnerErrorEnum::NothingToRedo => "nothingToRedo",
+            RunnerErrorEnum::GenerationOutOfRange { .. } => "generationOutOfRange",
+        }
+    }
+}
+
+impl fmt::Display for RunnerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.error {
+            RunnerErrorEnum::InputError { source } => write!(f, "invalid JSON: {source}"),
+            RunnerErrorEnum::StoreError { source } => write!(f, "invalid quiz: {source}"),
+            RunnerErrorEnum::StateError { source } => write!(f, "{source}"),
+            RunnerErrorEnum::NothingToUndo => write!(f, "nothing to undo"),
+            RunnerErrorEnum::NothingToRedo => write!(f, "nothing to redo"),
+            RunnerErrorEnum::GenerationOutOfRange { generation } => {
+                write!(f, "event log has no generation {generation}")
+            }
+        }
+    }
+}
+
+/// Wrapped errors are already part of the message, so they pass on their own source instead of
+/// being reported a second time.
+impl Error for RunnerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.error {
+            RunnerErrorEnum::InputError { source } => source.source(),
+            RunnerErrorEnum::StoreError { source } => source.source(),
+            RunnerErrorEnum::StateError { source } => source.source(),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for RunnerError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ErrorBody::new(self, &self.error).serialize(serializer)
+    }
 }
 
 impl From<JsonError> for RunnerError {
@@ -44,23 +117,64 @@ impl From<StateError> for RunnerError {
     }
 }
 
+/// How undo is recorded. `Truncate` drops undone events from the log, `Compensate` keeps the
+/// log append-only and records `Undo`/`Redo` markers instead.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum UndoMode {
+    #[default]
+    Truncate,
+    Compensate,
+}
+
 pub struct Runner {
+    store: QuizStore,
     state: QuizState,
     event_log: EventLog,
     view_cache: ViewCache,
+    clock: Box<dyn Clock>,
+    undo_mode: UndoMode,
+    redo_events: Vec<EventRecord>,
+}
+
+/// Every attempt gets its own pool draw and layout. The first one uses the recorded seed as is,
+/// so logs written before attempts existed replay unchanged.
+fn attempt_store(store: &QuizStore, seed: u64, attempt: usize) -> QuizStore {
+    let seed = match attempt {
+        1 => seed,
+        _ => Rng::new(seed.wrapping_add(attempt as u64)).next_u64(),
+    };
+
+    let mut store = store.clone();
+    store.randomize(seed);
+    store
 }
 
 impl Runner {
     pub fn new(input: &str) -> RunnerResult<Self> {
+        Self::new_with_seed(input, Rng::generate_seed())
+    }
+
+    /// Starts a run with a fixed seed for pool draws and shuffling. The seed is recorded in the
+    /// event log so the same questions and layout are restored by `new_with_events`.
+    pub fn new_with_seed(input: &str, seed: u64) -> RunnerResult<Self> {
         let input: QuizInput = serde_json::from_str(&input)?;
         let store = QuizStore::try_from(&input)?;
-        let event_log = EventLog::new(store.uid().clone(), store.version().clone(), Vec::new());
-        let state = QuizState::new(store);
+        let event_log = EventLog::new(
+            store.uid().clone(),
+            store.version().clone(),
+            seed,
+            Vec::new(),
+        );
+        let state = QuizState::new(attempt_store(&store, seed, 1));
 
         Ok(Self {
+            store,
             state,
             event_log,
             view_cache: ViewCache::new(),
+            clock: Box::new(SystemClock),
+            undo_mode: UndoMode::default(),
+            redo_events: Vec::new(),
         })
     }
 
@@ -68,25 +182,95 @@ impl Runner {
         let input: QuizInput = serde_json::from_str(&input)?;
         let store = QuizStore::try_from(&input)?;
         let event_log = serde_json::from_str::<EventLog>(&event_log_input)?;
-        let state = QuizState::new(store);
+        let state = QuizState::new(attempt_store(&store, *event_log.seed(), 1));
 
         let mut runner = Self {
+            store,
             state,
             event_log: EventLog::new(
                 event_log.uid().clone(),
                 event_log.version().clone(),
+                *event_log.seed(),
                 Vec::new(),
             ),
             view_cache: ViewCache::new(),
+            clock: Box::new(SystemClock),
+            undo_mode: UndoMode::default(),
+            redo_events: Vec::new(),
         };
 
-        for event in event_log.extract_events() {
-            runner.event(event)?;
+        for record in event_log.extract_events() {
+            runner.apply(record)?;
         }
 
         Ok(runner)
     }
 
+    pub fn with_undo_mode(mut self, undo_mode: UndoMode) -> Self {
+        self.undo_mode = undo_mode;
+        self
+    }
+
+    /// Replaces the system clock used to stamp new events and to compute remaining time.
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    pub fn start(&mut self) -> RunnerResult<QuizView> {
+        self.event(Event::StartQuiz)?;
+
+        Ok(self.quiz_view())
+    }
+
+    pub fn submit(&mut self) -> RunnerResult<QuizView> {
+        self.event(Event::SubmitQuiz)?;
+
+        Ok(self.quiz_view())
+    }
+
+    pub fn new_attempt(&mut self) -> RunnerResult<QuizView> {
+        self.event(Event::NewAttempt)?;
+
+        Ok(self.quiz_view())
+    }
+
+    /// Takes back the latest event still in effect. Starting, submitting or restarting the quiz
+    /// cannot be undone, nor can answers to a question that can no longer be updated.
+    pub fn undo(&mut self) -> RunnerResult<QuizView> {
+        match self.undo_mode {
+            UndoMode::Truncate => {
+                let record = self.step_back()?;
+                self.event_log.pop();
+                self.redo_events.push(record);
+                self.view_cache.clear();
+            }
+            UndoMode::Compensate => self.event(Event::Undo)?,
+        }
+
+        Ok(self.quiz_view())
+    }
+
+    pub fn redo(&mut self) -> RunnerResult<QuizView> {
+        match self.undo_mode {
+            UndoMode::Truncate => {
+                let record = self.redo_events.pop().ok_or(RunnerError {
+                    error: RunnerErrorEnum::NothingToRedo,
+                })?;
+
+                if let Err(error) = self.apply_event(&record) {
+                    self.redo_events.push(record);
+                    return Err(error);
+                }
+
+                self.event_log.push(record);
+            }
+            UndoMode::Compensate => self.event(Event::Redo)?,
+        }
+
+        Ok(self.quiz_view())
+    }
+
     pub fn select_answers(
         &mut self,
         question_id: usize,
@@ -113,26 +297,87 @@ impl Runner {
         self.question_view(question_id)
     }
 
+    pub fn order_answers(
+        &mut self,
+        question_id: usize,
+        answer_ids: Vec<usize>,
+    ) -> RunnerResult<QuestionView> {
+        self.event(Event::OrderAnswers {
+            question_id,
+            answer_ids,
+        })?;
+
+        self.question_view(question_id)
+    }
+
+    pub fn match_answers(
+        &mut self,
+        question_id: usize,
+        pairs: Vec<(usize, usize)>,
+    ) -> RunnerResult<QuestionView> {
+        self.event(Event::MatchAnswers { question_id, pairs })?;
+
+        self.question_view(question_id)
+    }
+
+    pub fn fill_blanks(
+        &mut self,
+        question_id: usize,
+        entries: Vec<BlankEntry>,
+    ) -> RunnerResult<QuestionView> {
+        self.event(Event::FillBlanks {
+            question_id,
+            entries,
+        })?;
+
+        self.question_view(question_id)
+    }
+
     pub fn clear_answers(&mut self, question_id: usize) -> RunnerResult<QuestionView> {
         self.event(Event::ClearAnswers { question_id })?;
 
         self.question_view(question_id)
     }
 
+    pub fn reveal_hint(&mut self, question_id: usize) -> RunnerResult<QuestionView> {
+        self.event(Event::RevealHint { question_id })?;
+
+        self.question_view(question_id)
+    }
+
+    pub fn grade_question(
+        &mut self,
+        question_id: usize,
+        points: f64,
+        feedback: Option<String>,
+        grader: String,
+    ) -> RunnerResult<QuestionView> {
+        self.event(Event::GradeQuestion {
+            question_id,
+            points,
+            feedback,
+            grader,
+        })?;
+
+        self.question_view(question_id)
+    }
+
     pub fn question_view(&mut self, question_id: usize) -> RunnerResult<QuestionView> {
+        self.refresh_clock();
+
         let question_store = self.state.find_question(question_id)?;
 
         let view = match self
             .view_cache
             .question(self.event_log.generation(), question_id)
         {
-            Some(view) => view,
+            Some(mut view) => {
+                view.refresh_remaining_time(&self.state);
+                view
+            }
             None => self.view_cache.cache_question(
                 self.event_log.generation(),
-                QuestionView::new(
-                    &question_store,
-                    self.state.question_state().get(&question_id),
-                ),
+                QuestionView::new(question_store, &self.state, &self.state.quiz_status()),
             ),
         };
 
@@ -140,16 +385,21 @@ impl Runner {
     }
 
     pub fn section_view(&mut self, section_id: usize) -> RunnerResult<SectionView> {
+        self.refresh_clock();
+
         let section_store = self.state.find_section(section_id)?;
 
         let view = match self
             .view_cache
             .section(self.event_log.generation(), section_id)
         {
-            Some(view) => view,
+            Some(mut view) => {
+                view.refresh_remaining_time(&self.state);
+                view
+            }
             None => self.view_cache.cache_section(
                 self.event_log.generation(),
-                SectionView::new(&section_store, &self.state),
+                SectionView::new(&section_store, &self.state, &self.state.quiz_status()),
             ),
         };
 
@@ -157,8 +407,13 @@ impl Runner {
     }
 
     pub fn quiz_view(&mut self) -> QuizView {
+        self.refresh_clock();
+
         match self.view_cache.quiz(self.event_log.generation()) {
-            Some(view) => view,
+            Some(mut view) => {
+                view.refresh_remaining_time(&self.state);
+                view
+            }
             None => self
                 .view_cache
                 .cache_quiz(self.event_log.generation(), QuizView::new(&self.state)),
@@ -169,8 +424,160 @@ impl Runner {
         &self.event_log
     }
 
+    /// Replays the first `generation` events into a separate runner, leaving this one untouched.
+    /// The replica's clock stands still at the last replayed event, so remaining time reads as it
+    /// did back then.
+    pub fn replay_until(&self, generation: usize) -> RunnerResult<Runner> {
+        if generation > self.event_log.generation() {
+            return Err(RunnerError {
+                error: RunnerErrorEnum::GenerationOutOfRange { generation },
+            });
+        }
+
+        let mut runner = self.replica();
+
+        for record in self.event_log.events().iter().take(generation) {
+            runner.replay(record.clone())?;
+        }
+
+        Ok(runner)
+    }
+
+    pub fn view_at(&self, generation: usize) -> RunnerResult<QuizView> {
+        Ok(self.replay_until(generation)?.quiz_view())
+    }
+
+    /// Quiz views before the first event and after each following one.
+    pub fn snapshots(&self) -> Snapshots<'_> {
+        Snapshots {
+            records: self.event_log.events(),
+            runner: self.replica(),
+            generation: 0,
+        }
+    }
+
+    /// Fresh runner over the same quiz, seed and undo mode, with an empty log.
+    fn replica(&self) -> Runner {
+        let seed = *self.event_log.seed();
+
+        Runner {
+            store: self.store.clone(),
+            state: QuizState::new(attempt_store(&self.store, seed, 1)),
+            event_log: EventLog::new(
+                self.event_log.uid().clone(),
+                *self.event_log.version(),
+                seed,
+                Vec::new(),
+            ),
+            view_cache: ViewCache::new(),
+            clock: Box::new(FixedClock::new(0)),
+            undo_mode: self.undo_mode.clone(),
+            redo_events: Vec::new(),
+        }
+    }
+
+    fn replay(&mut self, record: EventRecord) -> RunnerResult<()> {
+        if let Some(timestamp) = record.timestamp() {
+            self.clock = Box::new(FixedClock::new(*timestamp));
+        }
+
+        self.apply(record)
+    }
+
+    /// Remaining time moves with the clock even without new events. Cached views stay valid and
+    /// only have their remaining time refreshed when read.
+    fn refresh_clock(&mut self) {
+        if self.state.store().has_time_limits() {
+            self.state.set_now(self.clock.now());
+        }
+    }
+
     fn event(&mut self, event: Event) -> RunnerResult<()> {
-        match &event {
+        let timestamp = self.clock.now();
+        self.apply(EventRecord::new(Some(timestamp), event))
+    }
+
+    fn apply(&mut self, record: EventRecord) -> RunnerResult<()> {
+        match record.event() {
+            Event::Undo => {
+                let undone = self.step_back()?;
+                self.redo_events.push(undone);
+            }
+            Event::Redo => {
+                let redone = self.redo_events.pop().ok_or(RunnerError {
+                    error: RunnerErrorEnum::NothingToRedo,
+                })?;
+
+                if let Err(error) = self.apply_event(&redone) {
+                    self.redo_events.push(redone);
+                    return Err(error);
+                }
+            }
+            _ => {
+                self.apply_event(&record)?;
+                self.redo_events.clear();
+            }
+        }
+
+        self.event_log.push(record);
+
+        Ok(())
+    }
+
+    /// Rebuilds the state without the latest effective event and returns that event.
+    fn step_back(&mut self) -> RunnerResult<EventRecord> {
+        let mut records = self.event_log.effective_events();
+
+        let record = match records.pop() {
+            Some(record) if record.event().is_undoable() => record,
+            _ => {
+                return Err(RunnerError {
+                    error: RunnerErrorEnum::NothingToUndo,
+                })
+            }
+        };
+
+        if record.event().is_answer() {
+            if let Some(question_id) = record.event().question_id() {
+                self.state.check_update(question_id)?;
+            }
+        }
+
+        let mut replica = self.replica();
+
+        for record in records.iter() {
+            replica.apply_event(record)?;
+        }
+
+        self.state = replica.state;
+        self.view_cache.clear();
+
+        Ok(record)
+    }
+
+    fn apply_event(&mut self, record: &EventRecord) -> RunnerResult<()> {
+        let event = record.event();
+        let timestamp = match record.timestamp() {
+            Some(timestamp) if event.is_timed() => Some(*timestamp),
+            _ => None,
+        };
+
+        if let Some(timestamp) = timestamp {
+            self.state
+                .check_time_limits(event.question_id(), timestamp)?;
+        }
+
+        match event {
+            Event::StartQuiz => self.state.start()?,
+            Event::SubmitQuiz => self.state.submit()?,
+            Event::NewAttempt => {
+                let store = attempt_store(
+                    &self.store,
+                    *self.event_log.seed(),
+                    self.state.attempt() + 1,
+                );
+                self.state.new_attempt(store)?
+            }
             Event::SelectAnswers {
                 question_id,
                 answer_ids,
@@ -181,11 +588,984 @@ impl Runner {
                 question_id,
                 inputs,
             } => self.state.input_answers(*question_id, inputs.clone())?,
+            Event::OrderAnswers {
+                question_id,
+                answer_ids,
+            } => self.state.order_answers(*question_id, answer_ids.clone())?,
+            Event::MatchAnswers { question_id, pairs } => {
+                self.state.match_answers(*question_id, pairs.clone())?
+            }
+            Event::FillBlanks {
+                question_id,
+                entries,
+            } => self.state.fill_blanks(*question_id, entries.clone())?,
             Event::ClearAnswers { question_id } => self.state.clear_answers(*question_id)?,
+            Event::RevealHint { question_id } => self.state.reveal_hint(*question_id)?,
+            Event::GradeQuestion {
+                question_id,
+                points,
+                feedback,
+                grader,
+            } => self.state.grade_question(
+                *question_id,
+                *points,
+                feedback.clone(),
+                grader.clone(),
+            )?,
+            Event::Undo | Event::Redo => {}
         }
 
-        self.event_log.push(event);
+        if let Some(timestamp) = timestamp {
+            self.state.start_timers(timestamp);
+        }
 
         Ok(())
     }
 }
+
+pub struct Snapshots<'a> {
+    records: &'a [EventRecord],
+    runner: Runner,
+    generation: usize,
+}
+
+impl Iterator for Snapshots<'_> {
+    type Item = RunnerResult<QuizView>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.generation > self.records.len() {
+            return None;
+        }
+
+        if self.generation > 0 {
+            if let Err(error) = self
+                .runner
+                .replay(self.records[self.generation - 1].clone())
+            {
+                self.generation = self.records.len() + 1;
+                return Some(Err(error));
+            }
+        }
+
+        self.generation += 1;
+        Some(Ok(self.runner.quiz_view()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::StateErrorEnum;
+    use crate::store::{BOOLEAN_FALSE_ANSWER_ID, BOOLEAN_TRUE_ANSWER_ID};
+    use crate::view::{
+        AnswerViewStatus, QuestionAvailabilityView, QuestionViewStatus, QuizViewStatus,
+        SectionViewStatus,
+    };
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn it_judges_ordered_answers_per_position() {
+        let mut runner = Runner::new(include_str!("../tests/input/ordering_quiz.json")).unwrap();
+
+        let view = runner.order_answers(1, vec![2, 1, 3]).unwrap();
+        assert_eq!(view.status(), &QuestionViewStatus::AnsweredWrongly);
+        assert_eq!(
+            view.answers()
+                .iter()
+                .map(|answer| answer.status().clone())
+                .collect::<Vec<AnswerViewStatus>>(),
+            vec![
+                AnswerViewStatus::AnsweredCorrectly(0),
+                AnswerViewStatus::AnsweredWrongly,
+                AnswerViewStatus::AnsweredWrongly,
+            ]
+        );
+
+        let view = runner.order_answers(1, vec![2, 3, 1]).unwrap();
+        assert_eq!(view.status(), &QuestionViewStatus::AnsweredCorrectly);
+        assert!(runner.order_answers(2, vec![1, 2]).is_err());
+
+        let view = runner.order_answers(2, vec![1, 3, 2]).unwrap();
+        assert_eq!(view.status(), &QuestionViewStatus::AnsweredWrongly);
+        assert_eq!(view.score(), &(1.0 / 3.0));
+    }
+
+    #[test]
+    fn it_only_orders_answers_through_order_events() {
+        let mut runner = Runner::new(include_str!("../tests/input/ordering_quiz.json")).unwrap();
+
+        for error in [
+            runner.select_answers(1, vec![1, 2, 3]).unwrap_err(),
+            runner
+                .input_answers(1, vec!["1".into(), "2".into(), "3".into()])
+                .unwrap_err(),
+        ] {
+            assert!(matches!(
+                error.error(),
+                RunnerErrorEnum::StateError { source }
+                    if matches!(source.error(), StateErrorEnum::QuestionModeMismatch { question_id: 1 })
+            ));
+        }
+        assert_eq!(runner.event_log().events().len(), 0);
+    }
+
+    #[test]
+    fn it_judges_matched_pairs() {
+        let mut runner = Runner::new(include_str!("../tests/input/matching_quiz.json")).unwrap();
+
+        assert!(runner.match_answers(1, vec![(1, 3), (2, 3)]).is_err());
+        assert!(runner.match_answers(1, vec![(1, 4)]).is_err());
+
+        let view = runner
+            .match_answers(1, vec![(1, 3), (2, 2), (3, 1)])
+            .unwrap();
+        assert_eq!(view.status(), &QuestionViewStatus::AnsweredWrongly);
+        assert_eq!(view.answers()[0].match_id(), &Some(3));
+        assert_eq!(
+            view.answers()[0].status(),
+            &AnswerViewStatus::AnsweredCorrectly(0)
+        );
+        assert_eq!(
+            view.answers()[1].status(),
+            &AnswerViewStatus::AnsweredWrongly
+        );
+        assert_eq!(
+            view.match_answers()[2].status(),
+            &AnswerViewStatus::AnsweredCorrectly(0)
+        );
+
+        let view = runner
+            .match_answers(1, vec![(1, 3), (2, 1), (3, 2)])
+            .unwrap();
+        assert_eq!(view.status(), &QuestionViewStatus::AnsweredCorrectly);
+    }
+
+    #[test]
+    fn it_judges_cloze_blanks() {
+        let mut runner = Runner::new(include_str!("../tests/input/cloze_quiz.json")).unwrap();
+
+        let view = runner
+            .fill_blanks(
+                1,
+                vec![
+                    BlankEntry::Input {
+                        blank_id: 1,
+                        content: "Jupiter".into(),
+                    },
+                    BlankEntry::Selection {
+                        blank_id: 3,
+                        answer_id: 2,
+                    },
+                ],
+            )
+            .unwrap();
+        assert_eq!(view.status(), &QuestionViewStatus::AnsweredWrongly);
+        assert_eq!(
+            view.answers()
+                .iter()
+                .map(|answer| answer.status().clone())
+                .collect::<Vec<AnswerViewStatus>>(),
+            vec![
+                AnswerViewStatus::AnsweredCorrectly(0),
+                AnswerViewStatus::Pending,
+                AnswerViewStatus::AnsweredWrongly,
+            ]
+        );
+        assert_eq!(
+            view.blanks()[2].answers()[1].status(),
+            &AnswerViewStatus::AnsweredWrongly
+        );
+
+        let view = runner
+            .fill_blanks(
+                1,
+                vec![
+                    BlankEntry::Input {
+                        blank_id: 1,
+                        content: "jupiter".into(),
+                    },
+                    BlankEntry::Input {
+                        blank_id: 2,
+                        content: "4".into(),
+                    },
+                    BlankEntry::Selection {
+                        blank_id: 3,
+                        answer_id: 1,
+                    },
+                ],
+            )
+            .unwrap();
+        assert_eq!(view.status(), &QuestionViewStatus::AnsweredCorrectly);
+    }
+
+    #[test]
+    fn it_keeps_quiz_awaiting_review_until_essays_are_graded() {
+        let mut runner = Runner::new(include_str!("../tests/input/essay_quiz.json")).unwrap();
+
+        assert!(runner
+            .grade_question(2, 3.0, None, "teacher".into())
+            .is_err());
+
+        runner.input_answers(1, vec!["Cow".into()]).unwrap();
+        let view = runner
+            .input_answers(2, vec!["To find food in warmer regions".into()])
+            .unwrap();
+        assert_eq!(view.status(), &QuestionViewStatus::AwaitingReview);
+        assert_eq!(runner.quiz_view().status(), &QuizViewStatus::AwaitingReview);
+        assert!(runner
+            .grade_question(2, 6.0, None, "teacher".into())
+            .is_err());
+
+        let view = runner
+            .grade_question(
+                2,
+                3.5,
+                Some("Mention breeding too".into()),
+                "teacher".into(),
+            )
+            .unwrap();
+        assert_eq!(view.status(), &QuestionViewStatus::AnsweredCorrectly);
+        assert_eq!(view.score(), &3.5);
+        assert_eq!(
+            view.review()
+                .as_ref()
+                .map(|review| review.grader().as_str()),
+            Some("teacher")
+        );
+        assert_eq!(runner.quiz_view().status(), &QuizViewStatus::Completed);
+        assert_eq!(runner.quiz_view().score(), &4.5);
+    }
+
+    #[test]
+    fn it_expands_boolean_questions() {
+        let mut runner = Runner::new(include_str!("../tests/input/boolean_quiz.json")).unwrap();
+
+        let view = runner.question_view(1).unwrap();
+        assert_eq!(
+            view.answers()
+                .iter()
+                .map(|answer| answer.content().as_str())
+                .collect::<Vec<&str>>(),
+            vec!["Wahr", "Falsch"]
+        );
+        assert_eq!(
+            runner.question_view(2).unwrap().answers()[0].content(),
+            "Yes"
+        );
+
+        let view = runner
+            .select_answers(1, vec![BOOLEAN_FALSE_ANSWER_ID, BOOLEAN_TRUE_ANSWER_ID])
+            .unwrap();
+        assert_eq!(view.status(), &QuestionViewStatus::AnsweredCorrectly);
+
+        let view = runner
+            .select_answers(2, vec![BOOLEAN_FALSE_ANSWER_ID])
+            .unwrap();
+        assert_eq!(view.status(), &QuestionViewStatus::AnsweredWrongly);
+    }
+
+    #[test]
+    fn it_reveals_feedback_by_status() {
+        let mut runner = Runner::new(include_str!("../tests/input/feedback_quiz.json")).unwrap();
+
+        let view = runner.question_view(1).unwrap();
+        assert_eq!(view.feedback(), &None);
+        assert_eq!(view.explanation(), &None);
+
+        let view = runner.select_answers(1, vec![1, 2]).unwrap();
+        assert_eq!(view.feedback().as_deref(), Some("Not quite."));
+        assert_eq!(
+            view.explanation().as_deref(),
+            Some("Whales breathe air and nurse their young.")
+        );
+        assert_eq!(
+            view.answers()[0].feedback().as_deref(),
+            Some("Sharks are fish.")
+        );
+        assert_eq!(
+            view.answers()[1].feedback().as_deref(),
+            Some("Whales are mammals.")
+        );
+
+        let view = runner.input_answers(2, vec!["Owl".into()]).unwrap();
+        assert_eq!(view.feedback().as_deref(), Some("Thanks for your answer."));
+    }
+
+    #[test]
+    fn it_reveals_feedback_once_quiz_is_finished() {
+        let input = include_str!("../tests/input/feedback_quiz.json")
+            .replace("\"onStatus\"", "\"onQuizFinished\"");
+        let mut runner = Runner::new(&input).unwrap();
+
+        let view = runner.select_answers(1, vec![2]).unwrap();
+        assert_eq!(view.feedback(), &None);
+
+        runner.input_answers(2, vec!["Owl".into()]).unwrap();
+        let view = runner.question_view(1).unwrap();
+        assert_eq!(view.feedback().as_deref(), Some("Well done!"));
+        assert_eq!(
+            view.answers()[1].feedback().as_deref(),
+            Some("Whales are mammals.")
+        );
+    }
+
+    #[test]
+    fn it_reveals_feedback_on_match_and_blank_answers() {
+        let input = include_str!("../tests/input/matching_quiz.json").replace(
+            "{ \"id\": 3, \"content\": \"Hund\" }",
+            "{ \"id\": 3, \"content\": \"Hund\", \"feedback\": { \"correct\": \"Der Hund.\" } }",
+        );
+        let mut runner = Runner::new(&input).unwrap();
+
+        let view = runner
+            .match_answers(1, vec![(1, 3), (2, 1), (3, 2)])
+            .unwrap();
+        assert_eq!(
+            view.match_answers()[2].feedback().as_deref(),
+            Some("Der Hund.")
+        );
+
+        let input = include_str!("../tests/input/cloze_quiz.json").replace(
+            "{ \"id\": 2, \"content\": \"Moon\" }",
+            "{ \"id\": 2, \"content\": \"Moon\", \"feedback\": { \"wrong\": \"Moons orbit planets.\" } }",
+        );
+        let mut runner = Runner::new(&input).unwrap();
+
+        let view = runner
+            .fill_blanks(
+                1,
+                vec![BlankEntry::Selection {
+                    blank_id: 3,
+                    answer_id: 2,
+                }],
+            )
+            .unwrap();
+        assert_eq!(
+            view.blanks()[2].answers()[1].feedback().as_deref(),
+            Some("Moons orbit planets.")
+        );
+    }
+
+    #[test]
+    fn it_reveals_hints_and_deducts_their_cost() {
+        let mut runner = Runner::new(include_str!("../tests/input/hints_quiz.json")).unwrap();
+
+        let view = runner.reveal_hint(1).unwrap();
+        assert_eq!(view.hints(), &vec!["It lies on the Seine.".to_string()]);
+        assert_eq!(view.hints_count(), &2);
+        assert_eq!(view.assisted(), &true);
+        assert_eq!(view.score(), &0.0);
+
+        runner.reveal_hint(1).unwrap();
+        assert!(runner.reveal_hint(1).is_err());
+        assert_eq!(runner.quiz_view().score(), &0.0);
+
+        let view = runner.input_answers(1, vec!["Lyon".into()]).unwrap();
+        assert_eq!(view.score(), &0.0);
+
+        let view = runner.input_answers(1, vec!["Paris".into()]).unwrap();
+        assert_eq!(view.status(), &QuestionViewStatus::AnsweredCorrectly);
+        assert_eq!(view.hints().len(), 2);
+        assert_eq!(view.hint_cost(), &3.0);
+        assert_eq!(view.score(), &1.0);
+
+        runner.reveal_hint(2).unwrap();
+        let view = runner.input_answers(2, vec!["Madrid".into()]).unwrap();
+        assert_eq!(view.score(), &1.0);
+
+        let quiz_view = runner.quiz_view();
+        assert_eq!(quiz_view.assisted_questions_count(), &2);
+        assert_eq!(quiz_view.score(), &2.0);
+    }
+
+    #[test]
+    fn it_replays_shuffled_layout_from_seed() {
+        let input = include_str!("../tests/input/shuffled_quiz.json");
+        let answer_ids = |view: &QuizView| -> Vec<Vec<Option<usize>>> {
+            view.sections()[0]
+                .questions()
+                .iter()
+                .map(|question| question.answers().iter().map(|a| *a.id()).collect())
+                .collect()
+        };
+
+        let mut runner = Runner::new_with_seed(input, 7).unwrap();
+        runner.select_answers(1, vec![5, 6]).unwrap();
+        let view = runner.quiz_view();
+
+        let event_log = serde_json::to_string(runner.event_log()).unwrap();
+        let mut replayed = Runner::new_with_events(input, &event_log).unwrap();
+        let replayed_view = replayed.quiz_view();
+
+        assert_eq!(answer_ids(&view), answer_ids(&replayed_view));
+        assert_eq!(
+            view.sections()[0]
+                .questions()
+                .iter()
+                .map(|question| *question.id())
+                .collect::<Vec<usize>>(),
+            replayed_view.sections()[0]
+                .questions()
+                .iter()
+                .map(|question| *question.id())
+                .collect::<Vec<usize>>()
+        );
+
+        let mut select_ids = runner
+            .question_view(1)
+            .unwrap()
+            .answers()
+            .iter()
+            .map(|a| a.id().unwrap())
+            .collect::<Vec<usize>>();
+        assert_ne!(select_ids, vec![1, 2, 3, 4, 5, 6]);
+        select_ids.sort_unstable();
+        assert_eq!(select_ids, vec![1, 2, 3, 4, 5, 6]);
+
+        let boolean_ids = runner
+            .question_view(3)
+            .unwrap()
+            .answers()
+            .iter()
+            .map(|a| a.id().unwrap())
+            .collect::<Vec<usize>>();
+        assert_eq!(
+            boolean_ids,
+            vec![BOOLEAN_TRUE_ANSWER_ID, BOOLEAN_FALSE_ANSWER_ID]
+        );
+    }
+
+    #[test]
+    fn it_draws_pooled_questions_from_seed() {
+        let input = include_str!("../tests/input/pool_quiz.json");
+        let question_ids = |view: &QuizView| -> Vec<usize> {
+            view.sections()[0]
+                .questions()
+                .iter()
+                .map(|question| *question.id())
+                .collect()
+        };
+
+        let mut runner = Runner::new_with_seed(input, 11).unwrap();
+        let view = runner.quiz_view();
+        let drawn_ids = question_ids(&view);
+
+        assert_eq!(drawn_ids.len(), 3);
+        assert!(drawn_ids.iter().all(|id| (1..=4).contains(id)));
+        assert_eq!(view.max_score(), &3.0);
+
+        let undrawn_id = (1..=4).find(|id| !drawn_ids.contains(id)).unwrap();
+        assert!(runner
+            .select_answers(undrawn_id, vec![BOOLEAN_TRUE_ANSWER_ID])
+            .is_err());
+
+        for question_id in drawn_ids.iter() {
+            runner
+                .select_answers(*question_id, vec![BOOLEAN_TRUE_ANSWER_ID])
+                .unwrap();
+        }
+        assert_eq!(runner.quiz_view().status(), &QuizViewStatus::Completed);
+
+        let event_log = serde_json::to_string(runner.event_log()).unwrap();
+        let mut replayed = Runner::new_with_events(input, &event_log).unwrap();
+        assert_eq!(question_ids(&replayed.quiz_view()), drawn_ids);
+    }
+
+    #[test]
+    fn it_follows_branch_rules() {
+        let input = include_str!("../tests/input/branching_quiz.json");
+        let availability = |runner: &mut Runner, question_id: usize| {
+            runner
+                .quiz_view()
+                .sections()
+                .iter()
+                .flat_map(|section| section.questions())
+                .find(|question| question.id() == &question_id)
+                .map(|question| question.availability().clone())
+                .unwrap()
+        };
+
+        let mut runner = Runner::new(input).unwrap();
+        assert_eq!(
+            availability(&mut runner, 2),
+            QuestionAvailabilityView::Locked
+        );
+        assert_eq!(
+            availability(&mut runner, 4),
+            QuestionAvailabilityView::Locked
+        );
+
+        runner.select_answers(1, vec![2]).unwrap();
+        assert_eq!(
+            availability(&mut runner, 2),
+            QuestionAvailabilityView::Skipped
+        );
+        assert_eq!(
+            availability(&mut runner, 3),
+            QuestionAvailabilityView::Skipped
+        );
+        assert_eq!(
+            availability(&mut runner, 4),
+            QuestionAvailabilityView::Reachable
+        );
+        assert!(runner.input_answers(2, vec!["left".into()]).is_err());
+
+        runner.input_answers(4, vec!["Circle".into()]).unwrap();
+        assert_eq!(runner.quiz_view().status(), &QuizViewStatus::Completed);
+
+        let mut runner = Runner::new(input).unwrap();
+        runner.select_answers(1, vec![1]).unwrap();
+        assert_eq!(
+            availability(&mut runner, 2),
+            QuestionAvailabilityView::Reachable
+        );
+        assert_eq!(
+            availability(&mut runner, 3),
+            QuestionAvailabilityView::Locked
+        );
+        assert_eq!(
+            availability(&mut runner, 4),
+            QuestionAvailabilityView::Skipped
+        );
+
+        runner.input_answers(2, vec!["left".into()]).unwrap();
+        assert_eq!(
+            availability(&mut runner, 3),
+            QuestionAvailabilityView::Reachable
+        );
+    }
+
+    #[test]
+    fn it_requires_passing_each_section() {
+        let input = include_str!("../tests/input/sectioned_exam_quiz.json");
+
+        let mut runner = Runner::new(input).unwrap();
+        assert!(runner.input_answers(2, vec!["6".into()]).is_err());
+
+        runner
+            .select_answers(3, vec![BOOLEAN_TRUE_ANSWER_ID])
+            .unwrap();
+        let view = runner.section_view(2).unwrap();
+        assert_eq!(view.status(), &SectionViewStatus::Completed);
+        assert_eq!(view.correct_questions_count(), &1);
+
+        runner.input_answers(1, vec!["4".into()]).unwrap();
+        let view = runner.section_view(1).unwrap();
+        assert_eq!(view.status(), &SectionViewStatus::InProgress);
+        assert_eq!(view.answered_questions_count(), &1);
+        assert_eq!(runner.quiz_view().status(), &QuizViewStatus::InProgress);
+
+        runner.input_answers(2, vec!["6".into()]).unwrap();
+        let view = runner.section_view(1).unwrap();
+        assert_eq!(view.status(), &SectionViewStatus::Completed);
+        assert_eq!(view.score(), &2.0);
+        assert_eq!(runner.quiz_view().status(), &QuizViewStatus::Completed);
+
+        let mut runner = Runner::new(input).unwrap();
+        runner
+            .select_answers(3, vec![BOOLEAN_FALSE_ANSWER_ID])
+            .unwrap();
+        assert_eq!(
+            runner.section_view(2).unwrap().status(),
+            &SectionViewStatus::Failed
+        );
+        assert_eq!(runner.quiz_view().status(), &QuizViewStatus::Failed);
+    }
+
+    #[test]
+    fn it_opens_sections_of_a_linear_quiz_in_order() {
+        let input = include_str!("../tests/input/sectioned_exam_quiz.json")
+            .replacen("\"mode\": \"open\",", "\"mode\": \"linear\",", 1)
+            .replace(
+                "\"title\": \"Part B\",",
+                "\"title\": \"Part B\", \"mode\": \"open\",",
+            );
+        let mut runner = Runner::new(&input).unwrap();
+
+        assert!(runner
+            .select_answers(3, vec![BOOLEAN_TRUE_ANSWER_ID])
+            .is_err());
+        assert!(runner.input_answers(2, vec!["6".into()]).is_err());
+
+        runner.input_answers(1, vec!["4".into()]).unwrap();
+        runner.input_answers(2, vec!["6".into()]).unwrap();
+        runner
+            .select_answers(3, vec![BOOLEAN_TRUE_ANSWER_ID])
+            .unwrap();
+    }
+
+    #[test]
+    fn it_keeps_sections_without_thresholds_in_progress_until_answered() {
+        let mut runner = Runner::new(include_str!("../tests/input/lifecycle_quiz.json")).unwrap();
+        assert_eq!(
+            runner.section_view(1).unwrap().status(),
+            &SectionViewStatus::InProgress
+        );
+
+        runner.input_answers(1, vec!["4".into()]).unwrap();
+        runner.input_answers(2, vec!["6".into()]).unwrap();
+        assert_eq!(
+            runner.section_view(1).unwrap().status(),
+            &SectionViewStatus::Completed
+        );
+    }
+
+    #[test]
+    fn it_blocks_answers_once_submitted() {
+        let input = include_str!("../tests/input/lifecycle_quiz.json");
+        let mut runner = Runner::new(input).unwrap();
+        assert_eq!(runner.quiz_view().status(), &QuizViewStatus::NotStarted);
+
+        let view = runner.start().unwrap();
+        assert_eq!(view.status(), &QuizViewStatus::InProgress);
+        assert!(runner.start().is_err());
+
+        runner.input_answers(1, vec!["4".into()]).unwrap();
+        runner.input_answers(2, vec!["6".into()]).unwrap();
+        assert_eq!(runner.quiz_view().status(), &QuizViewStatus::InProgress);
+
+        let view = runner.submit().unwrap();
+        assert_eq!(view.status(), &QuizViewStatus::Submitted);
+        assert_eq!(view.correct_questions_count(), &2);
+        assert!(runner.input_answers(3, vec!["42".into()]).is_err());
+        assert!(runner.submit().is_err());
+
+        let event_log = serde_json::to_string(runner.event_log()).unwrap();
+        let mut replayed = Runner::new_with_events(input, &event_log).unwrap();
+        assert_eq!(replayed.quiz_view().status(), &QuizViewStatus::Submitted);
+    }
+
+    #[test]
+    fn it_awaits_review_of_submitted_essays() {
+        let mut runner = Runner::new(include_str!("../tests/input/essay_quiz.json")).unwrap();
+
+        runner
+            .input_answers(2, vec!["To find food in warmer regions".into()])
+            .unwrap();
+        assert_eq!(runner.quiz_view().status(), &QuizViewStatus::InProgress);
+
+        let view = runner.submit().unwrap();
+        assert_eq!(view.status(), &QuizViewStatus::AwaitingReview);
+
+        runner
+            .grade_question(2, 5.0, None, "teacher".into())
+            .unwrap();
+        assert_eq!(runner.quiz_view().status(), &QuizViewStatus::Completed);
+    }
+
+    #[test]
+    fn it_keeps_attempts_awaiting_review_open() {
+        let input = include_str!("../tests/input/essay_quiz.json")
+            .replace("\"passScore\": 4,", "\"passScore\": 4, \"maxAttempts\": 2,");
+        let mut runner = Runner::new(&input).unwrap();
+
+        runner.input_answers(1, vec!["Cow".into()]).unwrap();
+        runner
+            .input_answers(2, vec!["To find food in warmer regions".into()])
+            .unwrap();
+        assert_eq!(runner.quiz_view().final_score(), &None);
+        assert!(matches!(
+            runner.new_attempt().unwrap_err().error(),
+            RunnerErrorEnum::StateError { source }
+                if matches!(source.error(), StateErrorEnum::AttemptInProgress)
+        ));
+
+        runner
+            .grade_question(2, 3.5, None, "teacher".into())
+            .unwrap();
+        let view = runner.new_attempt().unwrap();
+        assert_eq!(view.attempts()[0].score(), &4.5);
+    }
+
+    #[test]
+    fn it_grades_submitted_essays_before_the_next_attempt() {
+        let input = include_str!("../tests/input/essay_quiz.json")
+            .replace("\"passScore\": 4,", "\"passScore\": 4, \"maxAttempts\": 2,");
+        let mut runner = Runner::new(&input).unwrap();
+
+        runner
+            .input_answers(2, vec!["To find food in warmer regions".into()])
+            .unwrap();
+        let view = runner.submit().unwrap();
+        assert_eq!(view.status(), &QuizViewStatus::AwaitingReview);
+        assert_eq!(view.final_score(), &None);
+        assert!(runner.new_attempt().is_err());
+
+        runner
+            .grade_question(2, 3.0, None, "teacher".into())
+            .unwrap();
+        assert_eq!(runner.quiz_view().final_score(), &Some(3.0));
+
+        let view = runner.new_attempt().unwrap();
+        assert_eq!(view.attempt(), &2);
+        assert_eq!(view.attempts()[0].score(), &3.0);
+    }
+
+    #[test]
+    fn it_tracks_attempts_and_keeps_best_score() {
+        let input = include_str!("../tests/input/lifecycle_quiz.json").replace(
+            "\"minAnsweredQuestions\": 3,",
+            "\"minAnsweredQuestions\": 3, \"maxAttempts\": 2, \"attemptScoring\": \"best\",",
+        );
+        let mut runner = Runner::new(&input).unwrap();
+        assert!(runner.new_attempt().is_err());
+
+        runner.input_answers(1, vec!["4".into()]).unwrap();
+        runner.submit().unwrap();
+
+        let view = runner.new_attempt().unwrap();
+        assert_eq!(view.attempt(), &2);
+        assert_eq!(view.status(), &QuizViewStatus::NotStarted);
+        assert_eq!(view.answered_questions_count(), &0);
+        assert_eq!(view.attempts().len(), 2);
+        assert_eq!(view.attempts()[0].status(), &QuizViewStatus::Submitted);
+        assert_eq!(view.attempts()[0].score(), &1.0);
+        assert_eq!(view.final_score(), &Some(1.0));
+
+        runner.input_answers(1, vec!["4".into()]).unwrap();
+        runner.input_answers(2, vec!["6".into()]).unwrap();
+        runner.input_answers(3, vec!["42".into()]).unwrap();
+        let view = runner.quiz_view();
+        assert_eq!(view.status(), &QuizViewStatus::Completed);
+        assert_eq!(view.final_score(), &Some(3.0));
+        assert!(runner.new_attempt().is_err());
+
+        let event_log = serde_json::to_string(runner.event_log()).unwrap();
+        let mut replayed = Runner::new_with_events(&input, &event_log).unwrap();
+        let view = replayed.quiz_view();
+        assert_eq!(view.attempt(), &2);
+        assert_eq!(view.final_score(), &Some(3.0));
+    }
+
+    #[test]
+    fn it_undoes_and_redoes_answers_by_truncating() {
+        let input = include_str!("../tests/input/lifecycle_quiz.json");
+        let content = |runner: &mut Runner| {
+            runner.question_view(1).unwrap().answers()[0]
+                .content()
+                .clone()
+        };
+
+        let mut runner = Runner::new(input).unwrap();
+        runner.start().unwrap();
+        runner.input_answers(1, vec!["5".into()]).unwrap();
+        runner.input_answers(1, vec!["4".into()]).unwrap();
+
+        runner.undo().unwrap();
+        assert_eq!(content(&mut runner), "5");
+        assert_eq!(runner.event_log().events().len(), 2);
+
+        runner.redo().unwrap();
+        assert_eq!(content(&mut runner), "4");
+        assert_eq!(runner.event_log().events().len(), 3);
+
+        runner.undo().unwrap();
+        runner.undo().unwrap();
+        assert!(runner.undo().is_err());
+        assert_eq!(runner.question_view(1).unwrap().answers().len(), 0);
+
+        runner.input_answers(2, vec!["6".into()]).unwrap();
+        assert!(runner.redo().is_err());
+    }
+
+    #[test]
+    fn it_undoes_answers_with_compensating_events() {
+        let input = include_str!("../tests/input/lifecycle_quiz.json");
+        let content = |runner: &mut Runner| {
+            runner.question_view(1).unwrap().answers()[0]
+                .content()
+                .clone()
+        };
+
+        let mut runner = Runner::new(input)
+            .unwrap()
+            .with_undo_mode(UndoMode::Compensate);
+        runner.input_answers(1, vec!["5".into()]).unwrap();
+        runner.input_answers(1, vec!["4".into()]).unwrap();
+
+        runner.undo().unwrap();
+        assert_eq!(content(&mut runner), "5");
+        assert_eq!(runner.event_log().events().len(), 3);
+
+        let event_log = serde_json::to_string(runner.event_log()).unwrap();
+        let mut replayed = Runner::new_with_events(input, &event_log)
+            .unwrap()
+            .with_undo_mode(UndoMode::Compensate);
+        assert_eq!(content(&mut replayed), "5");
+
+        replayed.redo().unwrap();
+        assert_eq!(content(&mut replayed), "4");
+        assert_eq!(replayed.event_log().events().len(), 4);
+    }
+
+    #[test]
+    fn it_undoes_revealed_hints_but_not_the_quiz_lifecycle() {
+        let mut runner = Runner::new(include_str!("../tests/input/hints_quiz.json")).unwrap();
+
+        runner.reveal_hint(1).unwrap();
+        runner.undo().unwrap();
+        assert!(runner.question_view(1).unwrap().hints().is_empty());
+
+        runner.redo().unwrap();
+        assert_eq!(runner.question_view(1).unwrap().hints().len(), 1);
+
+        runner.submit().unwrap();
+        let error = runner.undo().unwrap_err();
+        assert!(matches!(error.error(), RunnerErrorEnum::NothingToUndo));
+    }
+
+    #[test]
+    fn it_fails_once_required_questions_miss_the_pass_score() {
+        let input = include_str!("../tests/input/lifecycle_quiz.json")
+            .replace("\"minAnsweredQuestions\": 3,", "\"passScore\": 3,");
+        let mut runner = Runner::new(&input).unwrap();
+
+        runner.input_answers(1, vec!["4".into()]).unwrap();
+        assert_eq!(*runner.quiz_view().status(), QuizViewStatus::InProgress);
+
+        runner.input_answers(2, vec!["6".into()]).unwrap();
+        assert_eq!(*runner.quiz_view().status(), QuizViewStatus::Failed);
+    }
+
+    #[test]
+    fn it_reconstructs_views_at_earlier_generations() {
+        let input = include_str!("../tests/input/lifecycle_quiz.json");
+        let mut runner = Runner::new(input).unwrap();
+        runner.start().unwrap();
+        runner.input_answers(1, vec!["5".into()]).unwrap();
+        runner.input_answers(1, vec!["4".into()]).unwrap();
+        runner.input_answers(2, vec!["6".into()]).unwrap();
+
+        let mut past = runner.replay_until(2).unwrap();
+        assert_eq!(past.question_view(1).unwrap().answers()[0].content(), "5");
+        assert_eq!(*past.quiz_view().correct_questions_count(), 0);
+        assert_eq!(
+            *runner.view_at(0).unwrap().status(),
+            QuizViewStatus::NotStarted
+        );
+        assert!(runner.view_at(5).is_err());
+
+        let answered: Vec<usize> = runner
+            .snapshots()
+            .map(|view| *view.unwrap().answered_questions_count())
+            .collect();
+        assert_eq!(answered, vec![0, 0, 0, 1, 2]);
+        assert_eq!(runner.event_log().generation(), 4);
+        assert_eq!(runner.question_view(1).unwrap().answers()[0].content(), "4");
+    }
+
+    #[test]
+    fn it_reports_errors_with_codes() {
+        let input = include_str!("../tests/input/lifecycle_quiz.json");
+        let mut runner = Runner::new(input).unwrap();
+
+        let error = runner.undo().unwrap_err();
+        assert_eq!(error.code(), "nothingToUndo");
+        assert_eq!(error.to_string(), "nothing to undo");
+
+        let error = runner.input_answers(99, vec!["4".into()]).unwrap_err();
+        assert_eq!(error.code(), "stateError");
+        assert!(matches!(
+            error.error(),
+            RunnerErrorEnum::StateError { source }
+                if matches!(source.error(), StateErrorEnum::QuestionNotFound { question_id: 99 })
+        ));
+        assert!(error.source().is_none());
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "stateError",
+                "message": "question 99 not found",
+                "source": {
+                    "code": "questionNotFound",
+                    "message": "question 99 not found",
+                    "questionId": 99
+                }
+            })
+        );
+
+        let error = Runner::new("{").err().unwrap();
+        assert_eq!(error.code(), "inputError");
+    }
+
+    #[test]
+    fn it_refuses_to_undo_blocked_answers() {
+        let input = include_str!("../tests/input/lifecycle_quiz.json").replace(
+            "\"minAnsweredQuestions\": 3,",
+            "\"minAnsweredQuestions\": 3, \"blockAnswerUpdatesFor\": [\"answeredCorrectly\"],",
+        );
+        let mut runner = Runner::new(&input).unwrap();
+
+        runner.input_answers(1, vec!["4".into()]).unwrap();
+        assert!(runner.undo().is_err());
+        assert_eq!(runner.event_log().events().len(), 1);
+    }
+
+    #[test]
+    fn it_keeps_its_state_when_undo_fails_to_replay() {
+        let input = include_str!("../tests/input/lifecycle_quiz.json");
+        let mut runner = Runner::new(input).unwrap();
+
+        runner.input_answers(1, vec!["4".into()]).unwrap();
+        runner.event_log.push(EventRecord::new(
+            None,
+            Event::InputAnswers {
+                question_id: 99,
+                inputs: vec!["4".into()],
+            },
+        ));
+        runner.input_answers(2, vec!["6".into()]).unwrap();
+
+        assert!(runner.undo().is_err());
+        assert_eq!(runner.event_log().events().len(), 3);
+        assert_eq!(runner.quiz_view().answered_questions_count(), &2);
+        assert_eq!(
+            runner.question_view(2).unwrap().status(),
+            &QuestionViewStatus::AnsweredCorrectly
+        );
+    }
+
+    struct TestClock(Rc<Cell<u64>>);
+
+    impl Clock for TestClock {
+        fn now(&self) -> u64 {
+            self.0.get()
+        }
+    }
+
+    #[test]
+    fn it_rejects_answers_after_time_limits() {
+        let input = include_str!("../tests/input/timed_quiz.json");
+        let time = Rc::new(Cell::new(1_000_000));
+        let mut runner = Runner::new(input)
+            .unwrap()
+            .with_clock(TestClock(time.clone()));
+
+        assert_eq!(runner.quiz_view().remaining_time(), &Some(60_000));
+
+        runner.input_answers(1, vec!["4".into()]).unwrap();
+        assert_eq!(runner.quiz_view().remaining_time(), &Some(60_000));
+        time.set(1_005_000);
+        assert_eq!(runner.quiz_view().remaining_time(), &Some(55_000));
+
+        // Question 2 was available from the start, so its timer runs from the first answer.
+        let view = runner.input_answers(2, vec!["5".into()]).unwrap();
+        assert_eq!(view.remaining_time(), &Some(5_000));
+
+        time.set(1_020_000);
+        assert_eq!(runner.question_view(2).unwrap().remaining_time(), &Some(0));
+        assert!(runner.input_answers(2, vec!["6".into()]).is_err());
+        runner.input_answers(1, vec!["four".into()]).unwrap();
+
+        time.set(1_061_000);
+        assert!(runner.input_answers(1, vec!["4".into()]).is_err());
+
+        let event_log = serde_json::to_string(runner.event_log()).unwrap();
+        let replayed = Runner::new_with_events(input, &event_log).unwrap();
+        assert_eq!(replayed.event_log().events().len(), 3);
+
+        let late_event_log = event_log.replace("\"timestamp\":1020000", "\"timestamp\":1061000");
+        assert!(Runner::new_with_events(input, &late_event_log).is_err());
+    }
+}
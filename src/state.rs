@@ -1,27 +1,56 @@
-use crate::input::{QuestionStatusInput, QuizMode};
+use crate::event_log::BlankEntry;
+use crate::input::{
+    AttemptScoring, BranchConditionInput, BranchRuleInput, QuestionMode, QuestionStatusInput,
+    QuizMode, ScoringPolicy, ThresholdsInput,
+};
+use crate::store::ErrorBody;
 use crate::store::{CompiledEntryMatch, QuestionStore, QuizStore, SectionStore};
 use derive_getters::Getters;
+use serde::Serialize;
 use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
 
 type StateResult<T> = Result<T, StateError>;
 
-#[derive(Debug)]
+#[derive(Debug, Getters)]
 pub struct StateError {
-    error: StateErrorEnum,
+    pub(crate) error: StateErrorEnum,
 }
 
-#[derive(Debug)]
-enum StateErrorEnum {
+#[derive(Debug, Serialize)]
+#[serde(
+    tag = "code",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+#[non_exhaustive]
+pub enum StateErrorEnum {
     SectionNotFound {
         section_id: usize,
     },
     QuestionNotFound {
         question_id: usize,
     },
+    QuestionSkipped {
+        question_id: usize,
+    },
+    TimeLimitExceeded {
+        question_id: Option<usize>,
+        deadline: u64,
+    },
     AnswerNotFound {
         question_id: usize,
         answer_id: usize,
     },
+    MatchAnswerNotFound {
+        question_id: usize,
+        match_answer_id: usize,
+    },
+    BlankNotFound {
+        question_id: usize,
+        blank_id: usize,
+    },
     QuestionNotAvailable {
         question_id: usize,
     },
@@ -31,11 +60,154 @@ enum StateErrorEnum {
     QuestionCanNotBeUpdated {
         question_id: usize,
     },
+    QuestionModeMismatch {
+        question_id: usize,
+    },
+    QuestionCanNotBeGraded {
+        question_id: usize,
+    },
+    GradePointsOutOfRange {
+        question_id: usize,
+        points: f64,
+    },
+    NoHintsLeft {
+        question_id: usize,
+    },
     AnswerSelectionMismatch {
         question_id: usize,
         answer_ids: Vec<usize>,
     },
     QuizFinished,
+    QuizAlreadyStarted,
+    QuizSubmitted,
+    AttemptInProgress,
+    NoAttemptsLeft {
+        max_attempts: usize,
+    },
+}
+
+impl StateError {
+    /// Stable machine-readable code, also used as `code` in the serialized form.
+    pub fn code(&self) -> &'static str {
+        match self.error {
+            StateErrorEnum::SectionNotFound { .. } => "sectionNotFound",
+            StateErrorEnum::QuestionNotFound { .. } => "questionNotFound",
+            StateErrorEnum::QuestionSkipped { .. } => "questionSkipped",
+            StateErrorEnum::TimeLimitExceeded { .. } => "timeLimitExceeded",
+            StateErrorEnum::AnswerNotFound { .. } => "answerNotFound",
+            StateErrorEnum::MatchAnswerNotFound { .. } => "matchAnswerNotFound",
+            StateErrorEnum::BlankNotFound { .. } => "blankNotFound",
+            StateErrorEnum::QuestionNotAvailable { .. } => "questionNotAvailable",
+            StateErrorEnum::QuestionHasNoSelectableAnswers { .. } => {
+                "questionHasNoSelectableAnswers"
+            }
+            StateErrorEnum::QuestionCanNotBeUpdated { .. } => "questionCanNotBeUpdated",
+            StateErrorEnum::QuestionModeMismatch { .. } => "questionModeMismatch",
+            StateErrorEnum::QuestionCanNotBeGraded { .. } => "questionCanNotBeGraded",
+            StateErrorEnum::GradePointsOutOfRange { .. } => "gradePointsOutOfRange",
+            StateErrorEnum::NoHintsLeft { .. } => "noHintsLeft",
+            StateErrorEnum::AnswerSelectionMismatch { .. } => "answerSelectionMismatch",
+            StateErrorEnum::QuizFinished => "quizFinished",
+            StateErrorEnum::QuizAlreadyStarted => "quizAlreadyStarted",
+            StateErrorEnum::QuizSubmitted => "quizSubmitted",
+            StateErrorEnum::AttemptInProgress => "attemptInProgress",
+            StateErrorEnum::NoAttemptsLeft { .. } => "noAttemptsLeft",
+        }
+    }
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.error {
+            StateErrorEnum::SectionNotFound { section_id } => {
+                write!(f, "section {section_id} not found")
+            }
+            StateErrorEnum::QuestionNotFound { question_id } => {
+                write!(f, "question {question_id} not found")
+            }
+            StateErrorEnum::QuestionSkipped { question_id } => {
+                write!(f, "question {question_id} was skipped by a branch rule")
+            }
+            StateErrorEnum::TimeLimitExceeded {
+                question_id: Some(question_id),
+                deadline,
+            } => write!(
+                f,
+                "time limit for question {question_id} ran out at {deadline}"
+            ),
+            StateErrorEnum::TimeLimitExceeded { deadline, .. } => {
+                write!(f, "time limit ran out at {deadline}")
+            }
+            StateErrorEnum::AnswerNotFound {
+                question_id,
+                answer_id,
+            } => write!(f, "question {question_id} has no answer {answer_id}"),
+            StateErrorEnum::MatchAnswerNotFound {
+                question_id,
+                match_answer_id,
+            } => write!(
+                f,
+                "question {question_id} has no match answer {match_answer_id}"
+            ),
+            StateErrorEnum::BlankNotFound {
+                question_id,
+                blank_id,
+            } => write!(f, "question {question_id} has no blank {blank_id}"),
+            StateErrorEnum::QuestionNotAvailable { question_id } => {
+                write!(f, "question {question_id} is not available yet")
+            }
+            StateErrorEnum::QuestionHasNoSelectableAnswers { question_id } => {
+                write!(f, "question {question_id} has no selectable answers")
+            }
+            StateErrorEnum::QuestionCanNotBeUpdated { question_id } => {
+                write!(
+                    f,
+                    "answers to question {question_id} can no longer be updated"
+                )
+            }
+            StateErrorEnum::QuestionModeMismatch { question_id } => {
+                write!(
+                    f,
+                    "question {question_id} does not accept this kind of answer"
+                )
+            }
+            StateErrorEnum::QuestionCanNotBeGraded { question_id } => {
+                write!(f, "question {question_id} is not awaiting review")
+            }
+            StateErrorEnum::GradePointsOutOfRange {
+                question_id,
+                points,
+            } => write!(
+                f,
+                "{points} points are out of range for question {question_id}"
+            ),
+            StateErrorEnum::NoHintsLeft { question_id } => {
+                write!(f, "question {question_id} has no hints left")
+            }
+            StateErrorEnum::AnswerSelectionMismatch {
+                question_id,
+                answer_ids,
+            } => write!(
+                f,
+                "answers {answer_ids:?} do not fit the entry limits of question {question_id}"
+            ),
+            StateErrorEnum::QuizFinished => write!(f, "quiz is already finished"),
+            StateErrorEnum::QuizAlreadyStarted => write!(f, "quiz is already started"),
+            StateErrorEnum::QuizSubmitted => write!(f, "quiz is already submitted"),
+            StateErrorEnum::AttemptInProgress => write!(f, "current attempt is still in progress"),
+            StateErrorEnum::NoAttemptsLeft { max_attempts } => {
+                write!(f, "all {max_attempts} attempts are used up")
+            }
+        }
+    }
+}
+
+impl Error for StateError {}
+
+impl Serialize for StateError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ErrorBody::new(self, &self.error).serialize(serializer)
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -48,20 +220,20 @@ pub enum AnswerStateStatus {
 #[derive(Debug, Getters)]
 pub struct AnswerState {
     id: Option<usize>,
+    match_id: Option<usize>,
+    blank_id: Option<usize>,
     content: String,
+    points: Option<f64>,
     status: AnswerStateStatus,
 }
 
 impl AnswerState {
-    fn new_selection(question_store: &QuestionStore, answer_id: usize) -> StateResult<Self> {
-        let answer = question_store.answers().get(&answer_id).ok_or(StateError {
-            error: StateErrorEnum::AnswerNotFound {
-                question_id: question_store.id().clone(),
-                answer_id,
-            },
-        })?;
-
-        let status = match question_store.correct_entry_match() {
+    fn selection_status(
+        entry_match: Option<&CompiledEntryMatch>,
+        answer_id: usize,
+        content: &str,
+    ) -> AnswerStateStatus {
+        match entry_match {
             Some(entry_match) => match entry_match {
                 CompiledEntryMatch::Id { id: match_ids } => {
                     match match_ids.iter().position(|id| id == &answer_id) {
@@ -74,33 +246,52 @@ impl AnswerState {
                 } => {
                     match match_contents
                         .iter()
-                        .position(|match_content| match_content.is_match(answer.content()))
+                        .position(|match_content| match_content.is_match(content))
+                    {
+                        Some(index) => AnswerStateStatus::AnsweredCorrectly(index),
+                        None => AnswerStateStatus::AnsweredWrongly,
+                    }
+                }
+                CompiledEntryMatch::Numeric {
+                    numeric: match_numerics,
+                } => {
+                    match match_numerics
+                        .iter()
+                        .position(|match_numeric| match_numeric.is_match(content))
                     {
                         Some(index) => AnswerStateStatus::AnsweredCorrectly(index),
                         None => AnswerStateStatus::AnsweredWrongly,
                     }
                 }
+                CompiledEntryMatch::Pairs { pairs: _ } => AnswerStateStatus::Answered,
             },
             None => AnswerStateStatus::Answered,
-        };
-
-        Ok(Self {
-            id: Some(answer_id),
-            content: answer.content().clone(),
-            status,
-        })
+        }
     }
 
-    fn new_input(question_store: &QuestionStore, content: String) -> StateResult<Self> {
-        let status = match question_store.correct_entry_match() {
+    fn input_status(entry_match: Option<&CompiledEntryMatch>, content: &str) -> AnswerStateStatus {
+        match entry_match {
             Some(entry_match) => match entry_match {
-                CompiledEntryMatch::Id { id: _ } => AnswerStateStatus::Answered,
+                CompiledEntryMatch::Id { id: _ } | CompiledEntryMatch::Pairs { pairs: _ } => {
+                    AnswerStateStatus::Answered
+                }
                 CompiledEntryMatch::Content {
                     content: match_contents,
                 } => {
                     match match_contents
                         .iter()
-                        .position(|match_content| match_content.is_match(&content))
+                        .position(|match_content| match_content.is_match(content))
+                    {
+                        Some(index) => AnswerStateStatus::AnsweredCorrectly(index),
+                        None => AnswerStateStatus::AnsweredWrongly,
+                    }
+                }
+                CompiledEntryMatch::Numeric {
+                    numeric: match_numerics,
+                } => {
+                    match match_numerics
+                        .iter()
+                        .position(|match_numeric| match_numeric.is_match(content))
                     {
                         Some(index) => AnswerStateStatus::AnsweredCorrectly(index),
                         None => AnswerStateStatus::AnsweredWrongly,
@@ -108,11 +299,194 @@ impl AnswerState {
                 }
             },
             None => AnswerStateStatus::Answered,
+        }
+    }
+
+    fn new_selection(question_store: &QuestionStore, answer_id: usize) -> StateResult<Self> {
+        let answer = question_store.answers().get(&answer_id).ok_or(StateError {
+            error: StateErrorEnum::AnswerNotFound {
+                question_id: *question_store.id(),
+                answer_id,
+            },
+        })?;
+
+        let status = Self::selection_status(
+            question_store.correct_entry_match().as_ref(),
+            answer_id,
+            answer.content(),
+        );
+
+        Ok(Self {
+            id: Some(answer_id),
+            match_id: None,
+            blank_id: None,
+            content: answer.content().clone(),
+            points: *answer.points(),
+            status,
+        })
+    }
+
+    fn new_ordered(
+        question_store: &QuestionStore,
+        answer_id: usize,
+        position: usize,
+    ) -> StateResult<Self> {
+        let answer = question_store.answers().get(&answer_id).ok_or(StateError {
+            error: StateErrorEnum::AnswerNotFound {
+                question_id: *question_store.id(),
+                answer_id,
+            },
+        })?;
+
+        let is_correct = match question_store.correct_entry_match() {
+            Some(CompiledEntryMatch::Id { id: match_ids }) => {
+                Some(match_ids.get(position) == Some(&answer_id))
+            }
+            Some(CompiledEntryMatch::Content {
+                content: match_contents,
+            }) => Some(match match_contents.get(position) {
+                Some(match_content) => match_content.is_match(answer.content()),
+                None => false,
+            }),
+            Some(CompiledEntryMatch::Numeric {
+                numeric: match_numerics,
+            }) => Some(match match_numerics.get(position) {
+                Some(match_numeric) => match_numeric.is_match(answer.content()),
+                None => false,
+            }),
+            Some(CompiledEntryMatch::Pairs { pairs: _ }) | None => None,
+        };
+
+        let status = match is_correct {
+            Some(true) => AnswerStateStatus::AnsweredCorrectly(position),
+            Some(false) => AnswerStateStatus::AnsweredWrongly,
+            None => AnswerStateStatus::Answered,
+        };
+
+        Ok(Self {
+            id: Some(answer_id),
+            match_id: None,
+            blank_id: None,
+            content: answer.content().clone(),
+            points: *answer.points(),
+            status,
+        })
+    }
+
+    fn new_pair(
+        question_store: &QuestionStore,
+        answer_id: usize,
+        match_answer_id: usize,
+    ) -> StateResult<Self> {
+        let answer = question_store.answers().get(&answer_id).ok_or(StateError {
+            error: StateErrorEnum::AnswerNotFound {
+                question_id: *question_store.id(),
+                answer_id,
+            },
+        })?;
+
+        if !question_store
+            .match_answers()
+            .contains_key(&match_answer_id)
+        {
+            return Err(StateError {
+                error: StateErrorEnum::MatchAnswerNotFound {
+                    question_id: *question_store.id(),
+                    match_answer_id,
+                },
+            });
+        }
+
+        let status = match question_store.correct_entry_match() {
+            Some(CompiledEntryMatch::Pairs { pairs }) => match pairs
+                .iter()
+                .position(|pair| pair == &(answer_id, match_answer_id))
+            {
+                Some(index) => AnswerStateStatus::AnsweredCorrectly(index),
+                None => AnswerStateStatus::AnsweredWrongly,
+            },
+            _ => AnswerStateStatus::Answered,
+        };
+
+        Ok(Self {
+            id: Some(answer_id),
+            match_id: Some(match_answer_id),
+            blank_id: None,
+            content: answer.content().clone(),
+            points: *answer.points(),
+            status,
+        })
+    }
+
+    fn new_blank(question_store: &QuestionStore, entry: BlankEntry) -> StateResult<Self> {
+        let blank_id = entry.blank_id();
+        let blank_not_found = StateError {
+            error: StateErrorEnum::BlankNotFound {
+                question_id: *question_store.id(),
+                blank_id,
+            },
+        };
+
+        let blank = question_store
+            .blanks()
+            .get(&blank_id)
+            .ok_or(blank_not_found)?;
+        let blank_index = question_store
+            .blank_ids()
+            .iter()
+            .position(|id| id == &blank_id)
+            .unwrap_or(0);
+
+        let (id, content, points, status) = match entry {
+            BlankEntry::Selection { answer_id, .. } => {
+                let answer = blank.answers().get(&answer_id).ok_or(StateError {
+                    error: StateErrorEnum::AnswerNotFound {
+                        question_id: *question_store.id(),
+                        answer_id,
+                    },
+                })?;
+
+                (
+                    Some(answer_id),
+                    answer.content().clone(),
+                    *answer.points(),
+                    Self::selection_status(
+                        blank.correct_entry_match().as_ref(),
+                        answer_id,
+                        answer.content(),
+                    ),
+                )
+            }
+            BlankEntry::Input { content, .. } => {
+                let status = Self::input_status(blank.correct_entry_match().as_ref(), &content);
+                (None, content, None, status)
+            }
         };
 
+        Ok(Self {
+            id,
+            match_id: None,
+            blank_id: Some(blank_id),
+            content,
+            points,
+            status: match status {
+                AnswerStateStatus::AnsweredCorrectly(_) => {
+                    AnswerStateStatus::AnsweredCorrectly(blank_index)
+                }
+                status => status,
+            },
+        })
+    }
+
+    fn new_input(question_store: &QuestionStore, content: String) -> StateResult<Self> {
+        let status = Self::input_status(question_store.correct_entry_match().as_ref(), &content);
+
         Ok(Self {
             id: None,
+            match_id: None,
+            blank_id: None,
             content,
+            points: None,
             status,
         })
     }
@@ -124,6 +498,7 @@ pub enum QuestionStateStatus {
     Answered,
     AnsweredCorrectly,
     AnsweredWrongly,
+    AwaitingReview,
 }
 
 impl From<&QuestionStatusInput> for QuestionStateStatus {
@@ -133,14 +508,29 @@ impl From<&QuestionStatusInput> for QuestionStateStatus {
             QuestionStatusInput::Answered => Self::Answered,
             QuestionStatusInput::AnsweredCorrectly => Self::AnsweredCorrectly,
             QuestionStatusInput::AnsweredWrongly => Self::AnsweredWrongly,
+            QuestionStatusInput::AwaitingReview => Self::AwaitingReview,
         }
     }
 }
 
+#[derive(Debug, Getters)]
+pub struct ReviewState {
+    points: f64,
+    feedback: Option<String>,
+    grader: String,
+}
+
 #[derive(Debug, Getters)]
 pub struct QuestionState {
     answer_states: Vec<AnswerState>,
     status: QuestionStateStatus,
+    credit: f64,
+    points: f64,
+    penalty: f64,
+    score: f64,
+    review: Option<ReviewState>,
+    revealed_hints: usize,
+    hint_cost: f64,
 }
 
 impl QuestionState {
@@ -173,18 +563,116 @@ impl QuestionState {
         Ok(Self::new_with_answers(question_store, answers))
     }
 
+    fn new_with_order(question_store: &QuestionStore, answer_ids: Vec<usize>) -> StateResult<Self> {
+        let mut sorted_answer_ids = answer_ids.clone();
+        sorted_answer_ids.sort_unstable();
+        let mut expected_answer_ids = question_store.answer_ids().clone();
+        expected_answer_ids.sort_unstable();
+
+        if sorted_answer_ids != expected_answer_ids {
+            return Err(StateError {
+                error: StateErrorEnum::AnswerSelectionMismatch {
+                    question_id: *question_store.id(),
+                    answer_ids,
+                },
+            });
+        }
+
+        let answers = answer_ids
+            .iter()
+            .enumerate()
+            .map(|(position, id)| AnswerState::new_ordered(question_store, *id, position))
+            .collect::<StateResult<Vec<AnswerState>>>()?;
+        Ok(Self::new_with_answers(question_store, answers))
+    }
+
+    fn new_with_pairs(
+        question_store: &QuestionStore,
+        pairs: Vec<(usize, usize)>,
+    ) -> StateResult<Self> {
+        let mut answer_ids = HashSet::new();
+        let mut match_answer_ids = HashSet::new();
+
+        if !pairs.iter().all(|(answer_id, match_answer_id)| {
+            answer_ids.insert(*answer_id) && match_answer_ids.insert(*match_answer_id)
+        }) {
+            return Err(StateError {
+                error: StateErrorEnum::AnswerSelectionMismatch {
+                    question_id: *question_store.id(),
+                    answer_ids: pairs.iter().map(|(answer_id, _)| *answer_id).collect(),
+                },
+            });
+        }
+
+        let pairs: Vec<(usize, usize)> = match question_store.max_entries() {
+            Some(max_entries) => pairs.into_iter().take(*max_entries).collect(),
+            None => pairs,
+        };
+
+        let answers = pairs
+            .iter()
+            .map(|(answer_id, match_answer_id)| {
+                AnswerState::new_pair(question_store, *answer_id, *match_answer_id)
+            })
+            .collect::<StateResult<Vec<AnswerState>>>()?;
+        Ok(Self::new_with_answers(question_store, answers))
+    }
+
+    fn new_with_blanks(
+        question_store: &QuestionStore,
+        entries: Vec<BlankEntry>,
+    ) -> StateResult<Self> {
+        let mut blank_ids = HashSet::new();
+
+        if !entries
+            .iter()
+            .all(|entry| blank_ids.insert(entry.blank_id()))
+        {
+            return Err(StateError {
+                error: StateErrorEnum::AnswerSelectionMismatch {
+                    question_id: *question_store.id(),
+                    answer_ids: entries.iter().map(|entry| entry.blank_id()).collect(),
+                },
+            });
+        }
+
+        let answers = entries
+            .into_iter()
+            .map(|entry| AnswerState::new_blank(question_store, entry))
+            .collect::<StateResult<Vec<AnswerState>>>()?;
+        Ok(Self::new_with_answers(question_store, answers))
+    }
+
     fn new_with_answers(question_store: &QuestionStore, answer_states: Vec<AnswerState>) -> Self {
         if answer_states.len() < question_store.min_entries().unwrap_or(0) {
             return Self {
                 answer_states,
                 status: QuestionStateStatus::InProgress,
+                credit: 0.0,
+                points: 0.0,
+                penalty: 0.0,
+                score: 0.0,
+                review: None,
+                revealed_hints: 0,
+                hint_cost: 0.0,
             };
         }
 
-        let min_correct_entries = question_store.min_correct_entries().unwrap_or(0);
-        let max_wrong_entries = question_store
-            .max_wrong_entries()
-            .unwrap_or(answer_states.len());
+        let strict = matches!(
+            question_store.mode(),
+            QuestionMode::Order | QuestionMode::Match | QuestionMode::Cloze | QuestionMode::Boolean
+        );
+
+        let min_correct_entries = match question_store.min_correct_entries() {
+            Some(min_correct_entries) => *min_correct_entries,
+            None if strict => question_store.required_correct_entries(),
+            None => 0,
+        };
+        let max_wrong_entries = match question_store.max_wrong_entries() {
+            Some(max_wrong_entries) => *max_wrong_entries,
+            None if strict => 0,
+            None => answer_states.len(),
+        };
 
         let mut neutral_count = 0;
         let mut wrong_count = 0;
@@ -209,51 +697,380 @@ impl QuestionState {
             } else {
                 QuestionStateStatus::AnsweredWrongly
             }
+        } else if neutral_count > 0 && question_store.mode() == &QuestionMode::Essay {
+            QuestionStateStatus::AwaitingReview
         } else if neutral_count > 0 {
             QuestionStateStatus::Answered
+        } else if strict && wrong_count > 0 {
+            QuestionStateStatus::AnsweredWrongly
         } else {
             QuestionStateStatus::InProgress
         };
 
+        let (credit, points) = if question_store.has_answer_points() {
+            let points: f64 = answer_states
+                .iter()
+                .filter_map(|answer_state| answer_state.points)
+                .sum();
+            let points = points.min(question_store.max_points());
+
+            match question_store.max_points() {
+                max_points if max_points > 0.0 => (points / max_points, points),
+                _ => (0.0, points),
+            }
+        } else {
+            let credit = Self::compute_credit(question_store, &status, correct_count, wrong_count);
+            (credit, credit * question_store.max_points())
+        };
+
+        let penalty = question_store.wrong_entry_penalty().unwrap_or(0.0) * wrong_count as f64;
+
         Self {
             answer_states,
             status,
+            credit,
+            points,
+            penalty,
+            score: points - penalty,
+            review: None,
+            revealed_hints: 0,
+            hint_cost: 0.0,
+        }
+    }
+
+    fn apply_hints(&mut self, question_store: &QuestionStore, revealed_hints: usize) {
+        self.hint_cost = question_store.hint_cost(revealed_hints);
+        self.revealed_hints = revealed_hints;
+        self.score = self.assisted_points() - self.penalty;
+    }
+
+    /// Hints are paid for out of the points earned, so they never take a question below zero.
+    fn assisted_points(&self) -> f64 {
+        self.points - self.hint_cost.min(self.points.max(0.0))
+    }
+
+    fn grade(&mut self, question_store: &QuestionStore, review: ReviewState) {
+        let max_points = question_store.max_points();
+
+        self.status = if review.points > 0.0 {
+            QuestionStateStatus::AnsweredCorrectly
+        } else {
+            QuestionStateStatus::AnsweredWrongly
+        };
+        self.credit = if max_points > 0.0 {
+            review.points / max_points
+        } else {
+            0.0
+        };
+        self.points = review.points;
+        self.penalty = 0.0;
+        self.score = self.assisted_points();
+        self.review = Some(review);
+    }
+
+    fn compute_credit(
+        question_store: &QuestionStore,
+        status: &QuestionStateStatus,
+        correct_count: usize,
+        wrong_count: usize,
+    ) -> f64 {
+        let required_correct_entries = question_store.required_correct_entries();
+
+        match question_store.scoring_policy() {
+            ScoringPolicy::Proportional if required_correct_entries > 0 => {
+                correct_count.min(required_correct_entries) as f64 / required_correct_entries as f64
+            }
+            ScoringPolicy::RightMinusWrong if required_correct_entries > 0 => {
+                (correct_count.saturating_sub(wrong_count)).min(required_correct_entries) as f64
+                    / required_correct_entries as f64
+            }
+            _ => match status {
+                QuestionStateStatus::AnsweredCorrectly => 1.0,
+                _ => 0.0,
+            },
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum QuizStateStatus {
+    NotStarted,
     InProgress,
+    Submitted,
+    AwaitingReview,
     Completed,
     Failed,
 }
 
-#[derive(Debug, Getters)]
-pub struct QuizState {
-    store: QuizStore,
-    question_state: HashMap<usize, QuestionState>,
+#[derive(Debug, Clone, Getters)]
+pub struct AttemptResult {
+    attempt: usize,
+    status: QuizStateStatus,
+    score: f64,
+    max_score: f64,
+    answered_questions_count: usize,
+    correct_questions_count: usize,
+    wrong_questions_count: usize,
 }
 
-impl QuizState {
-    pub fn new(store: QuizStore) -> Self {
-        Self {
-            store,
-            question_state: HashMap::new(),
+#[derive(Debug, Clone, PartialEq)]
+pub enum SectionStateStatus {
+    InProgress,
+    AwaitingReview,
+    Completed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Default, Getters)]
+pub struct Progress {
+    questions_count: usize,
+    required_questions_count: usize,
+    answered_questions_count: usize,
+    required_answered_count: usize,
+    correct_questions_count: usize,
+    wrong_questions_count: usize,
+    awaiting_review_count: usize,
+    score: f64,
+    max_score: f64,
+    required_open: bool,
+}
+
+impl Progress {
+    /// Optional questions left open do not keep an out of reach pass score pending, unless there
+    /// is nothing but optional questions.
+    fn all_questions_answered(&self) -> bool {
+        match self.required_questions_count {
+            0 => self.answered_questions_count >= self.questions_count,
+            required_questions_count => self.required_answered_count >= required_questions_count,
         }
     }
+}
 
-    pub fn find_section(&self, section_id: usize) -> StateResult<&SectionStore> {
-        self.store.sections().get(&section_id).ok_or(StateError {
-            error: StateErrorEnum::SectionNotFound { section_id },
-        })
+fn evaluate_thresholds(thresholds: &ThresholdsInput, progress: &Progress) -> QuizStateStatus {
+    if progress.required_open {
+        return QuizStateStatus::InProgress;
     }
 
-    fn find_question_for_update(&self, question_id: usize) -> StateResult<&QuestionStore> {
-        if self.quiz_status() != QuizStateStatus::InProgress {
-            return Err(StateError {
-                error: StateErrorEnum::QuizFinished,
-            });
+    match (
+        thresholds.min_answered_questions(),
+        thresholds.max_answered_questions(),
+        thresholds.min_correct_questions(),
+        thresholds.max_wrong_questions(),
+        thresholds.min_score(),
+        thresholds.pass_score(),
+    ) {
+        (Some(min_answered_questions), _, _, _, _, _)
+            if progress.answered_questions_count < *min_answered_questions =>
+        {
+            QuizStateStatus::InProgress
+        }
+        (_, _, _, _, Some(min_score), _) if progress.score < *min_score => {
+            QuizStateStatus::InProgress
+        }
+        (_, _, _, Some(max_wrong_questions), _, _)
+            if progress.wrong_questions_count >= *max_wrong_questions =>
+        {
+            QuizStateStatus::Failed
+        }
+        (_, max_answered_questions, _, _, _, Some(pass_score)) if progress.score < *pass_score => {
+            match max_answered_questions {
+                Some(max_answered_questions)
+                    if progress.answered_questions_count >= *max_answered_questions =>
+                {
+                    QuizStateStatus::Failed
+                }
+                _ if progress.all_questions_answered() => QuizStateStatus::Failed,
+                _ => QuizStateStatus::InProgress,
+            }
+        }
+        (_, _, Some(min_correct_questions), _, _, _)
+            if progress.correct_questions_count >= *min_correct_questions =>
+        {
+            QuizStateStatus::Completed
+        }
+        (_, Some(max_answered_questions), _, _, _, _)
+            if progress.answered_questions_count >= *max_answered_questions =>
+        {
+            QuizStateStatus::Completed
+        }
+        _ => QuizStateStatus::Completed,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuestionAvailability {
+    Reachable,
+    Skipped,
+    Locked,
+}
+
+#[derive(Debug, Getters)]
+pub struct QuizState {
+    store: QuizStore,
+    attempt: usize,
+    attempts: Vec<AttemptResult>,
+    question_state: HashMap<usize, QuestionState>,
+    started: bool,
+    submitted: bool,
+    now: Option<u64>,
+    started_at: Option<u64>,
+    section_started_at: HashMap<usize, u64>,
+    question_started_at: HashMap<usize, u64>,
+    #[getter(skip)]
+    skipped_question_ids: HashSet<usize>,
+}
+
+/// Time limits are given in seconds, timestamps in milliseconds.
+fn deadline(started_at: Option<&u64>, time_limit: &Option<u64>) -> Option<u64> {
+    match (started_at, time_limit) {
+        (Some(started_at), Some(time_limit)) => Some(started_at + time_limit * 1000),
+        _ => None,
+    }
+}
+
+impl QuizState {
+    pub fn new(store: QuizStore) -> Self {
+        Self {
+            store,
+            attempt: 1,
+            attempts: Vec::new(),
+            question_state: HashMap::new(),
+            started: false,
+            submitted: false,
+            now: None,
+            started_at: None,
+            section_started_at: HashMap::new(),
+            question_started_at: HashMap::new(),
+            skipped_question_ids: HashSet::new(),
+        }
+    }
+
+    pub fn set_now(&mut self, now: u64) {
+        self.now = Some(now);
+    }
+
+    /// Rejects an event stamped after any deadline that applies to it. The quiz timer starts with
+    /// the first event, section and question timers once a question becomes available.
+    /// Questions of an open quiz are therefore timed from its start.
+    pub fn check_time_limits(&self, question_id: Option<usize>, timestamp: u64) -> StateResult<()> {
+        let mut deadlines = vec![self.quiz_deadline()];
+
+        if let Some(question_id) = question_id {
+            if let Some(section) = self.store.section_of(question_id) {
+                deadlines.push(self.section_deadline(*section.id()));
+            }
+            deadlines.push(self.question_deadline(question_id));
+        }
+
+        match deadlines
+            .into_iter()
+            .flatten()
+            .find(|deadline| timestamp > *deadline)
+        {
+            Some(deadline) => Err(StateError {
+                error: StateErrorEnum::TimeLimitExceeded {
+                    question_id,
+                    deadline,
+                },
+            }),
+            None => Ok(()),
+        }
+    }
+
+    pub fn start_timers(&mut self, timestamp: u64) {
+        self.started_at.get_or_insert(timestamp);
+
+        let available_question_ids: Vec<usize> = self
+            .store
+            .question_ids()
+            .iter()
+            .filter(|question_id| !self.question_started_at.contains_key(question_id))
+            .filter(|question_id| {
+                self.question_availability(**question_id) == QuestionAvailability::Reachable
+            })
+            .copied()
+            .collect();
+
+        for question_id in available_question_ids {
+            if let Some(section) = self.store.section_of(question_id) {
+                self.section_started_at
+                    .entry(*section.id())
+                    .or_insert(timestamp);
+            }
+            self.question_started_at.insert(question_id, timestamp);
+        }
+
+        self.now = Some(self.now.map_or(timestamp, |now| now.max(timestamp)));
+    }
+
+    pub fn quiz_deadline(&self) -> Option<u64> {
+        deadline(self.started_at.as_ref(), self.store.time_limit())
+    }
+
+    pub fn section_deadline(&self, section_id: usize) -> Option<u64> {
+        let section = self.store.sections().get(&section_id)?;
+        deadline(
+            self.section_started_at.get(&section_id),
+            section.time_limit(),
+        )
+    }
+
+    pub fn question_deadline(&self, question_id: usize) -> Option<u64> {
+        let question = self.store.questions().get(&question_id)?;
+        deadline(
+            self.question_started_at.get(&question_id),
+            question.time_limit(),
+        )
+    }
+
+    /// Milliseconds left before `deadline`, or the full limit while the timer has not started.
+    fn remaining_time(&self, deadline: Option<u64>, time_limit: &Option<u64>) -> Option<u64> {
+        match (deadline, time_limit) {
+            (Some(deadline), _) => Some(deadline.saturating_sub(self.now.unwrap_or(0))),
+            (None, Some(time_limit)) => Some(time_limit * 1000),
+            (None, None) => None,
+        }
+    }
+
+    pub fn quiz_remaining_time(&self) -> Option<u64> {
+        self.remaining_time(self.quiz_deadline(), self.store.time_limit())
+    }
+
+    pub fn section_remaining_time(&self, section_id: usize) -> Option<u64> {
+        let section = self.store.sections().get(&section_id)?;
+        self.remaining_time(self.section_deadline(section_id), section.time_limit())
+    }
+
+    pub fn question_remaining_time(&self, question_id: usize) -> Option<u64> {
+        let question = self.store.questions().get(&question_id)?;
+        self.remaining_time(self.question_deadline(question_id), question.time_limit())
+    }
+
+    pub fn find_section(&self, section_id: usize) -> StateResult<&SectionStore> {
+        self.store.sections().get(&section_id).ok_or(StateError {
+            error: StateErrorEnum::SectionNotFound { section_id },
+        })
+    }
+
+    pub fn check_update(&self, question_id: usize) -> StateResult<()> {
+        self.find_question_for_update(question_id).map(|_| ())
+    }
+
+    fn find_question_for_update(&self, question_id: usize) -> StateResult<&QuestionStore> {
+        if self.submitted {
+            return Err(StateError {
+                error: StateErrorEnum::QuizSubmitted,
+            });
+        }
+
+        match self.quiz_status() {
+            QuizStateStatus::NotStarted | QuizStateStatus::InProgress => {}
+            _ => {
+                return Err(StateError {
+                    error: StateErrorEnum::QuizFinished,
+                })
+            }
         }
 
         let question = self.find_question(question_id)?;
@@ -282,48 +1099,225 @@ impl QuizState {
             error: StateErrorEnum::QuestionNotFound { question_id },
         })?;
 
-        match self.store.quiz_mode() {
-            QuizMode::Open => Ok(question),
-            QuizMode::Linear => {
-                let previous_question_id = match self
-                    .store
-                    .question_ids()
-                    .iter()
-                    .position(|&id| id == question_id)
-                {
-                    Some(index) if index > 0 => Some(self.store.question_ids()[index - 1]),
-                    Some(_) => None,
-                    None => None,
-                };
-
-                match previous_question_id {
-                    Some(previous_question_id) => {
-                        match self.question_state.get(&previous_question_id) {
-                            Some(QuestionState {
-                                status:
-                                    QuestionStateStatus::InProgress
-                                    | QuestionStateStatus::Answered
-                                    | QuestionStateStatus::AnsweredWrongly,
-                                answer_states: _,
-                            })
-                            | None => Err(StateError {
-                                error: StateErrorEnum::QuestionNotAvailable { question_id },
-                            }),
-                            Some(QuestionState {
-                                status: QuestionStateStatus::AnsweredCorrectly,
-                                answer_states: _,
-                            }) => Ok(question),
-                        }
+        match self.question_availability(question_id) {
+            QuestionAvailability::Reachable => Ok(question),
+            QuestionAvailability::Skipped => Err(StateError {
+                error: StateErrorEnum::QuestionSkipped { question_id },
+            }),
+            QuestionAvailability::Locked => Err(StateError {
+                error: StateErrorEnum::QuestionNotAvailable { question_id },
+            }),
+        }
+    }
+
+    pub fn question_availability(&self, question_id: usize) -> QuestionAvailability {
+        if self.skipped_question_ids.contains(&question_id) {
+            return QuestionAvailability::Skipped;
+        }
+
+        // A section with its own mode is entered as a whole in a linear quiz and then gates its
+        // questions by that mode.
+        let section = self
+            .store
+            .section_of(question_id)
+            .filter(|section| section.mode().is_some());
+        let entry_question_id = section
+            .and_then(|section| section.question_ids().first().copied())
+            .unwrap_or(question_id);
+
+        if self.store.quiz_mode() == &QuizMode::Linear
+            && !self.previous_unlocks(self.store.question_ids(), entry_question_id)
+        {
+            return QuestionAvailability::Locked;
+        }
+
+        match section {
+            Some(section)
+                if section.mode() == &Some(QuizMode::Linear)
+                    && !self.previous_unlocks(section.question_ids(), question_id) =>
+            {
+                QuestionAvailability::Locked
+            }
+            _ => QuestionAvailability::Reachable,
+        }
+    }
+
+    /// Whether the last question on the path before `question_id` in `question_ids` lets the
+    /// candidate move on. The first question has nothing to wait for.
+    fn previous_unlocks(&self, question_ids: &[usize], question_id: usize) -> bool {
+        let previous_question_id = question_ids
+            .iter()
+            .take_while(|&&id| id != question_id)
+            .filter(|id| !self.skipped_question_ids.contains(id))
+            .last();
+
+        match previous_question_id {
+            Some(previous_question_id) => self.unlocks_next(*previous_question_id),
+            None => true,
+        }
+    }
+
+    /// A question unlocks its successor in linear mode once answered correctly. Questions with
+    /// branch rules route rather than gate, so any final answer lets the candidate move on.
+    fn unlocks_next(&self, question_id: usize) -> bool {
+        let has_branches = match self.store.questions().get(&question_id) {
+            Some(question) => !question.branches().is_empty(),
+            None => false,
+        };
+
+        match self.question_state.get(&question_id) {
+            Some(QuestionState {
+                status: QuestionStateStatus::AnsweredCorrectly,
+                ..
+            }) => true,
+            Some(QuestionState {
+                status: QuestionStateStatus::InProgress,
+                ..
+            })
+            | None => false,
+            Some(_) => has_branches,
+        }
+    }
+
+    /// Questions passed over by branch rules, as of the latest answer or grade.
+    pub fn skipped_question_ids(&self) -> &HashSet<usize> {
+        &self.skipped_question_ids
+    }
+
+    /// Branch rules only depend on question states, so they are evaluated once whenever one
+    /// changes rather than on every query.
+    fn refresh_skipped_question_ids(&mut self) {
+        self.skipped_question_ids = self.evaluate_branch_rules();
+    }
+
+    /// Rules are evaluated in question order, so rules of skipped questions, and of sections
+    /// skipped entirely, never fire. Section rules fire after the last question of their section.
+    fn evaluate_branch_rules(&self) -> HashSet<usize> {
+        let mut skipped_question_ids = HashSet::new();
+
+        for section_id in self.store.section_ids() {
+            let section = match self.store.sections().get(section_id) {
+                Some(section) => section,
+                None => continue,
+            };
+
+            for question_id in section.question_ids() {
+                if skipped_question_ids.contains(question_id) {
+                    continue;
+                }
+
+                if let Some(question) = self.store.questions().get(question_id) {
+                    for rule in question.branches() {
+                        self.apply_branch_rule(rule, *question_id, &mut skipped_question_ids);
                     }
-                    None => Ok(question),
+                }
+            }
+
+            let last_question_id = section
+                .question_ids()
+                .iter()
+                .rev()
+                .find(|question_id| !skipped_question_ids.contains(question_id));
+
+            if let Some(last_question_id) = last_question_id {
+                for rule in section.branches() {
+                    self.apply_branch_rule(rule, *last_question_id, &mut skipped_question_ids);
                 }
             }
         }
+
+        skipped_question_ids
     }
 
-    pub fn answered_questions_count(&self) -> usize {
+    fn apply_branch_rule(
+        &self,
+        rule: &BranchRuleInput,
+        from_question_id: usize,
+        skipped_question_ids: &mut HashSet<usize>,
+    ) {
+        let action = match self.branch_condition_met(rule.when()) {
+            Some(true) => rule.then(),
+            Some(false) => rule.otherwise(),
+            None => return,
+        };
+
+        let action = match action {
+            Some(action) => action,
+            None => return,
+        };
+
+        let question_ids = self.store.question_ids();
+        let target_question_id = match (action.go_to_question(), action.go_to_section()) {
+            (Some(question_id), _) => Some(*question_id),
+            (None, Some(section_id)) => self
+                .store
+                .sections()
+                .get(section_id)
+                .and_then(|section| section.question_ids().first().copied()),
+            (None, None) => None,
+        };
+
+        let from_index = question_ids.iter().position(|&id| id == from_question_id);
+        let target_index = target_question_id.and_then(|target_question_id| {
+            question_ids.iter().position(|&id| id == target_question_id)
+        });
+
+        if let (Some(from_index), Some(target_index)) = (from_index, target_index) {
+            if target_index > from_index {
+                skipped_question_ids.extend(question_ids[from_index + 1..target_index].iter());
+            }
+        }
+
+        skipped_question_ids.extend(action.skip_questions().iter());
+
+        for section_id in action.skip_sections() {
+            if let Some(section) = self.store.sections().get(section_id) {
+                skipped_question_ids.extend(section.question_ids().iter());
+            }
+        }
+    }
+
+    /// `None` while the referenced question is still unanswered, so neither branch applies yet.
+    fn branch_condition_met(&self, condition: &BranchConditionInput) -> Option<bool> {
+        let question_state = self.question_state.get(condition.question_id())?;
+
+        if question_state.status == QuestionStateStatus::InProgress {
+            return None;
+        }
+
+        let answers_match = match condition.answer_ids() {
+            Some(answer_ids) => answer_ids.iter().all(|answer_id| {
+                question_state
+                    .answer_states
+                    .iter()
+                    .any(|answer_state| answer_state.id == Some(*answer_id))
+            }),
+            None => true,
+        };
+
+        let status_match = match condition.status() {
+            Some(status) => question_state.status == QuestionStateStatus::from(status),
+            None => true,
+        };
+
+        Some(answers_match && status_match)
+    }
+
+    /// States of questions still on the candidate's path. Answers left behind on questions that a
+    /// later branch skipped do not count.
+    fn active_question_states(&self) -> Vec<&QuestionState> {
+        let skipped_question_ids = self.skipped_question_ids();
+
         self.question_state
-            .values()
+            .iter()
+            .filter(|(question_id, _)| !skipped_question_ids.contains(question_id))
+            .map(|(_, question_state)| question_state)
+            .collect()
+    }
+
+    pub fn answered_questions_count(&self) -> usize {
+        self.active_question_states()
+            .into_iter()
             .filter(|q| match q.status() {
                 QuestionStateStatus::InProgress => false,
                 _ => true,
@@ -332,8 +1326,8 @@ impl QuizState {
     }
 
     pub fn correct_questions_count(&self) -> usize {
-        self.question_state
-            .values()
+        self.active_question_states()
+            .into_iter()
             .filter(|q| match q.status() {
                 QuestionStateStatus::AnsweredCorrectly => true,
                 _ => false,
@@ -342,8 +1336,8 @@ impl QuizState {
     }
 
     pub fn wrong_questions_count(&self) -> usize {
-        self.question_state
-            .values()
+        self.active_question_states()
+            .into_iter()
             .filter(|q| match q.status() {
                 QuestionStateStatus::AnsweredWrongly => true,
                 _ => false,
@@ -351,59 +1345,272 @@ impl QuizState {
             .count()
     }
 
+    pub fn score(&self) -> f64 {
+        self.active_question_states()
+            .into_iter()
+            .map(|q| q.score)
+            .sum()
+    }
+
+    pub fn penalty(&self) -> f64 {
+        self.active_question_states()
+            .into_iter()
+            .map(|q| q.penalty)
+            .sum()
+    }
+
+    pub fn assisted_questions_count(&self) -> usize {
+        self.active_question_states()
+            .into_iter()
+            .filter(|q| q.revealed_hints > 0)
+            .count()
+    }
+
+    pub fn awaiting_review_count(&self) -> usize {
+        self.active_question_states()
+            .into_iter()
+            .filter(|q| q.status() == &QuestionStateStatus::AwaitingReview)
+            .count()
+    }
+
+    /// Answering a question starts the quiz implicitly. A quiz handed in before reaching its
+    /// thresholds is `Submitted` rather than left in progress.
     pub fn quiz_status(&self) -> QuizStateStatus {
-        if self
+        if !self.started {
+            return QuizStateStatus::NotStarted;
+        }
+
+        match self.threshold_status() {
+            QuizStateStatus::InProgress if !self.submitted => QuizStateStatus::InProgress,
+            _ if self.awaiting_review_count() > 0 => QuizStateStatus::AwaitingReview,
+            QuizStateStatus::InProgress => QuizStateStatus::Submitted,
+            status => status,
+        }
+    }
+
+    /// The quiz only finishes once every section has, and fails as soon as any section fails.
+    fn threshold_status(&self) -> QuizStateStatus {
+        let section_statuses: Vec<SectionStateStatus> = self
             .store
-            .questions()
-            .values()
-            .filter_map(|question_store| {
-                if *question_store.optional() {
-                    None
-                } else {
-                    Some(question_store.id().clone())
-                }
-            })
-            .any(|question_id| match self.question_state.get(&question_id) {
-                Some(question_state)
-                    if question_state.status() == &QuestionStateStatus::InProgress
-                        || question_state.status() == &QuestionStateStatus::AnsweredWrongly =>
-                {
-                    true
-                }
-                _ => false,
-            })
-        {
-            return QuizStateStatus::InProgress;
+            .section_ids()
+            .iter()
+            .filter_map(|section_id| self.store.sections().get(section_id))
+            .map(|section| self.section_threshold_status(section))
+            .collect();
+
+        if section_statuses.contains(&SectionStateStatus::Failed) {
+            return QuizStateStatus::Failed;
         }
 
-        match (
-            self.store.min_answered_questions(),
-            self.store.max_answered_questions(),
-            self.store.min_correct_questions(),
-            self.store.max_wrong_questions(),
+        match evaluate_thresholds(
+            self.store.thresholds(),
+            &self.progress(self.store.question_ids()),
         ) {
-            (Some(min_answered_questions), _, _, _)
-                if self.answered_questions_count() < *min_answered_questions =>
-            {
+            QuizStateStatus::InProgress => QuizStateStatus::InProgress,
+            _ if section_statuses.contains(&SectionStateStatus::InProgress) => {
                 QuizStateStatus::InProgress
             }
-            (_, _, _, Some(max_wrong_questions))
-                if self.wrong_questions_count() >= *max_wrong_questions =>
-            {
-                QuizStateStatus::Failed
+            status => status,
+        }
+    }
+
+    /// A section without thresholds of its own stays in progress until each of its required
+    /// questions has been answered.
+    pub fn section_status(&self, section_id: usize) -> StateResult<SectionStateStatus> {
+        let section = self.find_section(section_id)?;
+
+        Ok(match self.section_threshold_status(section) {
+            SectionStateStatus::Completed if section.thresholds().is_empty() => {
+                let progress = self.progress(section.question_ids());
+
+                if progress.required_answered_count < progress.required_questions_count {
+                    SectionStateStatus::InProgress
+                } else {
+                    SectionStateStatus::Completed
+                }
             }
-            (_, _, Some(min_correct_questions), _)
-                if self.correct_questions_count() >= *min_correct_questions =>
-            {
-                QuizStateStatus::Completed
+            status => status,
+        })
+    }
+
+    /// Status by the section's thresholds alone, which is all the quiz waits for.
+    fn section_threshold_status(&self, section: &SectionStore) -> SectionStateStatus {
+        let progress = self.progress(section.question_ids());
+
+        if progress.questions_count == 0 && !section.question_ids().is_empty() {
+            return SectionStateStatus::Skipped;
+        }
+
+        match evaluate_thresholds(section.thresholds(), &progress) {
+            QuizStateStatus::InProgress => SectionStateStatus::InProgress,
+            _ if progress.awaiting_review_count > 0 => SectionStateStatus::AwaitingReview,
+            QuizStateStatus::Failed => SectionStateStatus::Failed,
+            _ => SectionStateStatus::Completed,
+        }
+    }
+
+    pub fn section_progress(&self, section_id: usize) -> StateResult<Progress> {
+        let section = self.find_section(section_id)?;
+        Ok(self.progress(section.question_ids()))
+    }
+
+    /// Tallies the given questions, leaving out those skipped by branch rules.
+    fn progress(&self, question_ids: &[usize]) -> Progress {
+        let skipped_question_ids = self.skipped_question_ids();
+        let mut progress = Progress::default();
+
+        for question_id in question_ids
+            .iter()
+            .filter(|question_id| !skipped_question_ids.contains(question_id))
+        {
+            let question = match self.store.questions().get(question_id) {
+                Some(question) => question,
+                None => continue,
+            };
+
+            progress.questions_count += 1;
+            progress.max_score += question.max_points();
+
+            if !question.optional() {
+                progress.required_questions_count += 1;
             }
-            (_, Some(max_answered_questions), _, _)
-                if self.answered_questions_count() >= *max_answered_questions =>
-            {
-                QuizStateStatus::Completed
+
+            if let Some(question_state) = self.question_state.get(question_id) {
+                progress.score += question_state.score;
+
+                match question_state.status {
+                    QuestionStateStatus::InProgress => {}
+                    QuestionStateStatus::AnsweredCorrectly => {
+                        progress.answered_questions_count += 1;
+                        progress.correct_questions_count += 1;
+                    }
+                    QuestionStateStatus::AnsweredWrongly => {
+                        progress.answered_questions_count += 1;
+                        progress.wrong_questions_count += 1;
+                    }
+                    QuestionStateStatus::AwaitingReview => {
+                        progress.answered_questions_count += 1;
+                        progress.awaiting_review_count += 1;
+                    }
+                    QuestionStateStatus::Answered => progress.answered_questions_count += 1,
+                }
+
+                if !question.optional() && question_state.status != QuestionStateStatus::InProgress
+                {
+                    progress.required_answered_count += 1;
+                }
+
+                if !question.optional()
+                    && matches!(
+                        question_state.status,
+                        QuestionStateStatus::InProgress | QuestionStateStatus::AnsweredWrongly
+                    )
+                {
+                    progress.required_open = true;
+                }
+            }
+        }
+
+        progress
+    }
+
+    pub fn start(&mut self) -> StateResult<()> {
+        if self.started {
+            return Err(StateError {
+                error: StateErrorEnum::QuizAlreadyStarted,
+            });
+        }
+
+        self.started = true;
+        Ok(())
+    }
+
+    pub fn submit(&mut self) -> StateResult<()> {
+        if self.submitted {
+            return Err(StateError {
+                error: StateErrorEnum::QuizSubmitted,
+            });
+        }
+
+        self.started = true;
+        self.submitted = true;
+        Ok(())
+    }
+
+    /// Archives the finished attempt and starts over on `store`, which may hold a different
+    /// draw and layout. Everything else in the state only ever reflects the current attempt.
+    pub fn new_attempt(&mut self, store: QuizStore) -> StateResult<()> {
+        if self.is_attempt_open() {
+            return Err(StateError {
+                error: StateErrorEnum::AttemptInProgress,
+            });
+        }
+
+        if let Some(max_attempts) = self.store.max_attempts() {
+            if self.attempt >= *max_attempts {
+                return Err(StateError {
+                    error: StateErrorEnum::NoAttemptsLeft {
+                        max_attempts: *max_attempts,
+                    },
+                });
             }
-            _ => QuizStateStatus::Completed,
         }
+
+        let mut attempts = std::mem::take(&mut self.attempts);
+        attempts.push(self.attempt_result());
+
+        *self = Self {
+            attempt: self.attempt + 1,
+            attempts,
+            ..Self::new(store)
+        };
+
+        Ok(())
+    }
+
+    /// An attempt awaiting review stays open, as archiving it would freeze its ungraded score.
+    fn is_attempt_open(&self) -> bool {
+        matches!(
+            self.quiz_status(),
+            QuizStateStatus::NotStarted
+                | QuizStateStatus::InProgress
+                | QuizStateStatus::AwaitingReview
+        )
+    }
+
+    pub fn attempt_result(&self) -> AttemptResult {
+        AttemptResult {
+            attempt: self.attempt,
+            status: self.quiz_status(),
+            score: self.score(),
+            max_score: self.store.max_score(),
+            answered_questions_count: self.answered_questions_count(),
+            correct_questions_count: self.correct_questions_count(),
+            wrong_questions_count: self.wrong_questions_count(),
+        }
+    }
+
+    /// Score across all finished attempts, combined according to the quiz's attempt scoring.
+    /// The current attempt counts once it is finished, and there is no final score while it
+    /// awaits review.
+    pub fn final_score(&self) -> Option<f64> {
+        let mut scores: Vec<f64> = self.attempts.iter().map(|attempt| attempt.score).collect();
+
+        match self.quiz_status() {
+            QuizStateStatus::AwaitingReview => return None,
+            QuizStateStatus::NotStarted | QuizStateStatus::InProgress => {}
+            _ => scores.push(self.score()),
+        }
+
+        if scores.is_empty() {
+            return None;
+        }
+
+        Some(match self.store.attempt_scoring() {
+            AttemptScoring::Best => scores.iter().cloned().fold(f64::MIN, f64::max),
+            AttemptScoring::Last => scores[scores.len() - 1],
+            AttemptScoring::Average => scores.iter().sum::<f64>() / scores.len() as f64,
+        })
     }
 
     pub fn select_answers(
@@ -412,21 +1619,261 @@ impl QuizState {
         answer_ids: Vec<usize>,
     ) -> StateResult<()> {
         let question = self.find_question_for_update(question_id)?;
+
+        if matches!(
+            question.mode(),
+            QuestionMode::Order | QuestionMode::Match | QuestionMode::Cloze | QuestionMode::Essay
+        ) {
+            return Err(StateError {
+                error: StateErrorEnum::QuestionModeMismatch { question_id },
+            });
+        }
+
         let question_state = QuestionState::new_with_selections(&question, answer_ids)?;
-        self.question_state.insert(question_id, question_state);
+        self.update_question_state(question_id, question_state);
         Ok(())
     }
 
     pub fn input_answers(&mut self, question_id: usize, inputs: Vec<String>) -> StateResult<()> {
         let question = self.find_question_for_update(question_id)?;
+
+        // Select questions have always taken typed entries matched by content, as in the
+        // open exam event log, so only the modes with their own events are turned away.
+        if matches!(
+            question.mode(),
+            QuestionMode::Order | QuestionMode::Match | QuestionMode::Cloze | QuestionMode::Boolean
+        ) {
+            return Err(StateError {
+                error: StateErrorEnum::QuestionModeMismatch { question_id },
+            });
+        }
+
         let question_state = QuestionState::new_with_inputs(&question, inputs)?;
+        self.update_question_state(question_id, question_state);
+        Ok(())
+    }
+
+    pub fn order_answers(&mut self, question_id: usize, answer_ids: Vec<usize>) -> StateResult<()> {
+        let question = self.find_question_for_update(question_id)?;
+
+        if question.mode() != &QuestionMode::Order {
+            return Err(StateError {
+                error: StateErrorEnum::QuestionModeMismatch { question_id },
+            });
+        }
+
+        let question_state = QuestionState::new_with_order(question, answer_ids)?;
+        self.update_question_state(question_id, question_state);
+        Ok(())
+    }
+
+    pub fn match_answers(
+        &mut self,
+        question_id: usize,
+        pairs: Vec<(usize, usize)>,
+    ) -> StateResult<()> {
+        let question = self.find_question_for_update(question_id)?;
+
+        if question.mode() != &QuestionMode::Match {
+            return Err(StateError {
+                error: StateErrorEnum::QuestionModeMismatch { question_id },
+            });
+        }
+
+        let question_state = QuestionState::new_with_pairs(question, pairs)?;
+        self.update_question_state(question_id, question_state);
+        Ok(())
+    }
+
+    pub fn fill_blanks(&mut self, question_id: usize, entries: Vec<BlankEntry>) -> StateResult<()> {
+        let question = self.find_question_for_update(question_id)?;
+
+        if question.mode() != &QuestionMode::Cloze {
+            return Err(StateError {
+                error: StateErrorEnum::QuestionModeMismatch { question_id },
+            });
+        }
+
+        let question_state = QuestionState::new_with_blanks(question, entries)?;
+        self.update_question_state(question_id, question_state);
+        Ok(())
+    }
+
+    pub fn grade_question(
+        &mut self,
+        question_id: usize,
+        points: f64,
+        feedback: Option<String>,
+        grader: String,
+    ) -> StateResult<()> {
+        let question = self.store.questions().get(&question_id).ok_or(StateError {
+            error: StateErrorEnum::QuestionNotFound { question_id },
+        })?;
+
+        if question.mode() != &QuestionMode::Essay {
+            return Err(StateError {
+                error: StateErrorEnum::QuestionModeMismatch { question_id },
+            });
+        }
+
+        if !(0.0..=question.max_points()).contains(&points) {
+            return Err(StateError {
+                error: StateErrorEnum::GradePointsOutOfRange {
+                    question_id,
+                    points,
+                },
+            });
+        }
+
+        match self.question_state.get_mut(&question_id) {
+            Some(question_state)
+                if question_state.review.is_some()
+                    || question_state.status == QuestionStateStatus::AwaitingReview =>
+            {
+                question_state.grade(
+                    question,
+                    ReviewState {
+                        points,
+                        feedback,
+                        grader,
+                    },
+                );
+                self.refresh_skipped_question_ids();
+                Ok(())
+            }
+            _ => Err(StateError {
+                error: StateErrorEnum::QuestionCanNotBeGraded { question_id },
+            }),
+        }
+    }
+
+    pub fn reveal_hint(&mut self, question_id: usize) -> StateResult<()> {
+        let question = self.find_question_for_update(question_id)?;
+        let revealed_hints = match self.question_state.get(&question_id) {
+            Some(question_state) => question_state.revealed_hints + 1,
+            None => 1,
+        };
+
+        if revealed_hints > question.hints().len() {
+            return Err(StateError {
+                error: StateErrorEnum::NoHintsLeft { question_id },
+            });
+        }
+
+        let empty_state = QuestionState::new_with_answers(question, Vec::new());
+        let mut question_state = self
+            .question_state
+            .remove(&question_id)
+            .unwrap_or(empty_state);
+
+        if let Some(question) = self.store.questions().get(&question_id) {
+            question_state.apply_hints(question, revealed_hints);
+        }
+
         self.question_state.insert(question_id, question_state);
+        self.refresh_skipped_question_ids();
+        self.started = true;
         Ok(())
     }
 
     pub fn clear_answers(&mut self, question_id: usize) -> StateResult<()> {
         let question = self.find_question_for_update(question_id)?;
-        self.question_state.remove(&question_id);
+
+        match self.question_state.get(&question_id) {
+            Some(question_state) if question_state.revealed_hints > 0 => {
+                let question_state = QuestionState::new_with_answers(question, Vec::new());
+                self.update_question_state(question_id, question_state);
+            }
+            _ => {
+                self.question_state.remove(&question_id);
+                self.refresh_skipped_question_ids();
+                self.started = true;
+            }
+        }
+
         Ok(())
     }
+
+    fn update_question_state(&mut self, question_id: usize, mut question_state: QuestionState) {
+        if let (Some(previous_state), Some(question)) = (
+            self.question_state.get(&question_id),
+            self.store.questions().get(&question_id),
+        ) {
+            question_state.apply_hints(question, previous_state.revealed_hints);
+        }
+
+        self.question_state.insert(question_id, question_state);
+        self.refresh_skipped_question_ids();
+        self.started = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::QuizInput;
+
+    fn quiz_state(input_json: &str) -> QuizState {
+        let quiz_input = serde_json::from_str::<QuizInput>(input_json).unwrap();
+        QuizState::new(QuizStore::try_from(&quiz_input).unwrap())
+    }
+
+    #[test]
+    fn it_scores_weighted_questions() {
+        let mut state = quiz_state(include_str!("../tests/input/scored_exam_quiz.json"));
+
+        state.select_answers(1, vec![1, 2]).unwrap();
+        state.select_answers(2, vec![1, 3]).unwrap();
+
+        assert_eq!(state.question_state()[&1].score(), &3.0);
+        assert_eq!(state.question_state()[&2].score(), &0.5);
+        assert_eq!(state.store().max_score(), 6.0);
+        assert_eq!(state.score(), 3.5);
+        assert_eq!(state.quiz_status(), QuizStateStatus::InProgress);
+    }
+
+    #[test]
+    fn it_applies_pass_score_once_all_questions_are_answered() {
+        let mut state = quiz_state(include_str!("../tests/input/scored_exam_quiz.json"));
+
+        state.select_answers(1, vec![1, 2]).unwrap();
+        state.select_answers(2, vec![2, 3]).unwrap();
+        state.input_answers(3, vec!["Goat".into()]).unwrap();
+
+        assert_eq!(state.score(), 3.5);
+        assert_eq!(state.quiz_status(), QuizStateStatus::Failed);
+    }
+
+    #[test]
+    fn it_awards_partial_credit() {
+        let mut state = quiz_state(include_str!("../tests/input/partial_credit_quiz.json"));
+
+        state
+            .input_answers(1, vec!["Cow".into(), "Pig".into(), "Cat".into()])
+            .unwrap();
+        state.select_answers(2, vec![1, 2, 3, 5]).unwrap();
+
+        assert_eq!(state.question_state()[&1].credit(), &0.5);
+        assert_eq!(state.question_state()[&1].score(), &1.0);
+        assert_eq!(state.question_state()[&2].credit(), &0.5);
+        assert_eq!(state.question_state()[&2].score(), &2.0);
+        assert_eq!(state.score(), 3.0);
+    }
+
+    #[test]
+    fn it_deducts_penalties_for_wrong_entries() {
+        let mut state = quiz_state(include_str!("../tests/input/negative_marking_quiz.json"));
+
+        state.select_answers(2, vec![1, 3]).unwrap();
+        state.select_answers(3, vec![1, 2]).unwrap();
+        state.select_answers(1, vec![2]).unwrap();
+
+        assert_eq!(state.question_state()[&1].penalty(), &0.0);
+        assert_eq!(state.question_state()[&2].penalty(), &0.25);
+        assert_eq!(state.question_state()[&2].score(), &0.75);
+        assert_eq!(state.question_state()[&3].score(), &0.0);
+        assert_eq!(state.penalty(), 1.25);
+        assert_eq!(state.score(), 2.75);
+        assert_eq!(state.quiz_status(), QuizStateStatus::Failed);
+    }
 }
@@ -1,20 +1,36 @@
 use crate::input::{
-    AnswerInput, EntryMatch, QuestionInput, QuestionMode, QuestionStatusInput, QuizInput, QuizMode,
-    SectionInput,
+    max_points, AnswerInput, AttemptScoring, BlankInput, BooleanLabels, BranchRuleInput,
+    EntryMatch, FeedbackInput, FeedbackReveal, HintInput, NumericMatch, PoolInput, QuestionInput,
+    QuestionMode, QuestionStatusInput, QuizInput, QuizMode, ScoringPolicy, SectionInput,
+    ThresholdsInput,
 };
+use crate::random::Rng;
 use derive_getters::Getters;
 use regex::Regex;
+use serde::Serialize;
 use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::sync::OnceLock;
 
 type StoreResult<T> = Result<T, StoreError>;
 
-#[derive(Debug)]
+pub const BOOLEAN_TRUE_ANSWER_ID: usize = 1;
+pub const BOOLEAN_FALSE_ANSWER_ID: usize = 0;
+
+#[derive(Debug, Getters)]
 pub struct StoreError {
     error: StoreErrorEnum,
 }
 
-#[derive(Debug)]
-enum StoreErrorEnum {
+#[derive(Debug, Serialize)]
+#[serde(
+    tag = "code",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+#[non_exhaustive]
+pub enum StoreErrorEnum {
     DuplicateSectionId {
         section_id: usize,
     },
@@ -26,11 +42,178 @@ enum StoreErrorEnum {
         question_id: usize,
         answer_id: usize,
     },
+    DuplicateBlankId {
+        question_id: usize,
+        blank_id: usize,
+    },
+    BlankNotFound {
+        question_id: usize,
+        blank_id: usize,
+    },
+    PlaceholderNotFound {
+        question_id: usize,
+        blank_id: usize,
+    },
+    PairAnswerNotFound {
+        question_id: usize,
+        answer_id: usize,
+        match_answer_id: usize,
+    },
+    BranchTargetNotFound {
+        question_id: Option<usize>,
+        section_id: Option<usize>,
+    },
+    BackwardBranchTarget {
+        question_id: Option<usize>,
+        section_id: Option<usize>,
+    },
+    PoolTooSmall {
+        section_id: usize,
+        count: usize,
+        available: usize,
+    },
+    BooleanAnswersNotAllowed {
+        question_id: usize,
+    },
     RegexError {
+        #[serde(skip)]
         source: regex::Error,
     },
 }
 
+impl StoreError {
+    /// Stable machine-readable code, also used as `code` in the serialized form.
+    pub fn code(&self) -> &'static str {
+        match self.error {
+            StoreErrorEnum::DuplicateSectionId { .. } => "duplicateSectionId",
+            StoreErrorEnum::DuplicateQuestionId { .. } => "duplicateQuestionId",
+            StoreErrorEnum::DuplicateAnswerId { .. } => "duplicateAnswerId",
+            StoreErrorEnum::DuplicateBlankId { .. } => "duplicateBlankId",
+            StoreErrorEnum::BlankNotFound { .. } => "blankNotFound",
+            StoreErrorEnum::PlaceholderNotFound { .. } => "placeholderNotFound",
+            StoreErrorEnum::PairAnswerNotFound { .. } => "pairAnswerNotFound",
+            StoreErrorEnum::BranchTargetNotFound { .. } => "branchTargetNotFound",
+            StoreErrorEnum::BackwardBranchTarget { .. } => "backwardBranchTarget",
+            StoreErrorEnum::PoolTooSmall { .. } => "poolTooSmall",
+            StoreErrorEnum::BooleanAnswersNotAllowed { .. } => "booleanAnswersNotAllowed",
+            StoreErrorEnum::RegexError { .. } => "regexError",
+        }
+    }
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.error {
+            StoreErrorEnum::DuplicateSectionId { section_id } => {
+                write!(f, "section {section_id} is defined more than once")
+            }
+            StoreErrorEnum::DuplicateQuestionId {
+                section_id,
+                question_id,
+            } => write!(
+                f,
+                "question {question_id} in section {section_id} is defined more than once"
+            ),
+            StoreErrorEnum::DuplicateAnswerId {
+                question_id,
+                answer_id,
+            } => write!(
+                f,
+                "answer {answer_id} of question {question_id} is defined more than once"
+            ),
+            StoreErrorEnum::DuplicateBlankId {
+                question_id,
+                blank_id,
+            } => write!(
+                f,
+                "blank {blank_id} of question {question_id} is defined more than once"
+            ),
+            StoreErrorEnum::BlankNotFound {
+                question_id,
+                blank_id,
+            } => write!(f, "question {question_id} has no blank {blank_id}"),
+            StoreErrorEnum::PlaceholderNotFound {
+                question_id,
+                blank_id,
+            } => write!(
+                f,
+                "blank {blank_id} of question {question_id} has no placeholder in the content"
+            ),
+            StoreErrorEnum::PairAnswerNotFound {
+                question_id,
+                answer_id,
+                match_answer_id,
+            } => write!(
+                f,
+                "question {question_id} pairs answer {answer_id} with unknown match answer {match_answer_id}"
+            ),
+            StoreErrorEnum::BranchTargetNotFound {
+                question_id: Some(question_id),
+                ..
+            } => write!(f, "branch targets unknown question {question_id}"),
+            StoreErrorEnum::BranchTargetNotFound {
+                section_id: Some(section_id),
+                ..
+            } => write!(f, "branch targets unknown section {section_id}"),
+            StoreErrorEnum::BranchTargetNotFound { .. } => write!(f, "branch target not found"),
+            StoreErrorEnum::BackwardBranchTarget {
+                question_id: Some(question_id),
+                ..
+            } => write!(f, "branch goes back to question {question_id}"),
+            StoreErrorEnum::BackwardBranchTarget {
+                section_id: Some(section_id),
+                ..
+            } => write!(f, "branch goes back to section {section_id}"),
+            StoreErrorEnum::BackwardBranchTarget { .. } => write!(f, "branch goes backward"),
+            StoreErrorEnum::PoolTooSmall {
+                section_id,
+                count,
+                available,
+            } => write!(
+                f,
+                "section {section_id} draws {count} questions but only {available} are eligible"
+            ),
+            StoreErrorEnum::BooleanAnswersNotAllowed { question_id } => write!(
+                f,
+                "boolean question {question_id} takes `correct` instead of answers and correctEntryMatch"
+            ),
+            StoreErrorEnum::RegexError { .. } => write!(f, "invalid answer pattern"),
+        }
+    }
+}
+
+impl Error for StoreError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.error {
+            StoreErrorEnum::RegexError { source } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for StoreError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ErrorBody::new(self, &self.error).serialize(serializer)
+    }
+}
+
+/// Serialized form of the public errors: the variant's `code` and fields plus a `message`.
+#[derive(Serialize)]
+pub(crate) struct ErrorBody<'a, E: Serialize> {
+    message: String,
+    #[serde(flatten)]
+    error: &'a E,
+}
+
+impl<'a, E: Serialize> ErrorBody<'a, E> {
+    pub(crate) fn new(display: &impl fmt::Display, error: &'a E) -> Self {
+        Self {
+            message: display.to_string(),
+            error,
+        }
+    }
+}
+
 impl From<regex::Error> for StoreError {
     fn from(item: regex::Error) -> Self {
         Self {
@@ -39,66 +222,395 @@ impl From<regex::Error> for StoreError {
     }
 }
 
-#[derive(Debug, Getters)]
+#[derive(Debug, Clone, Getters)]
 pub struct AnswerStore {
     id: usize,
     content: String,
+    points: Option<f64>,
+    feedback: Option<FeedbackInput>,
+}
+
+#[derive(Debug, Clone, Getters)]
+pub struct CompiledNumericMatch {
+    value: Option<f64>,
+    tolerance: f64,
+    relative_tolerance: f64,
+    min: Option<f64>,
+    max: Option<f64>,
+    unit: Option<String>,
+    require_unit: bool,
+}
+
+impl From<&NumericMatch> for CompiledNumericMatch {
+    fn from(numeric: &NumericMatch) -> Self {
+        Self {
+            value: *numeric.value(),
+            tolerance: numeric.tolerance().unwrap_or(0.0),
+            relative_tolerance: numeric.relative_tolerance().unwrap_or(0.0),
+            min: *numeric.min(),
+            max: *numeric.max(),
+            unit: numeric
+                .unit()
+                .as_ref()
+                .map(|unit| unit.trim().to_lowercase()),
+            require_unit: *numeric.require_unit(),
+        }
+    }
+}
+
+impl CompiledNumericMatch {
+    /// Accepts both decimal separators and an optional trailing unit, e.g. "3,14" or "9.81 m/s2".
+    /// A single separator followed by exactly three digits, as in "1,000" or "3.140", may just as
+    /// well group thousands, so such entries match if either reading does.
+    pub fn is_match(&self, content: &str) -> bool {
+        static NUMBER: OnceLock<Regex> = OnceLock::new();
+        static AMBIGUOUS: OnceLock<Regex> = OnceLock::new();
+
+        let number = NUMBER.get_or_init(|| {
+            Regex::new(r"^\s*([+-]?(?:\d+(?:[.,]\d*)?|[.,]\d+)(?:[eE][+-]?\d+)?)\s*(.*?)\s*$")
+                .unwrap()
+        });
+
+        let captures = match number.captures(content) {
+            Some(captures) => captures,
+            None => return false,
+        };
+
+        let ambiguous = AMBIGUOUS
+            .get_or_init(|| Regex::new(r"^[+-]?[1-9]\d{0,2}[.,]\d{3}(?:[eE][+-]?\d+)?$").unwrap());
+
+        let mut readings = vec![captures[1].replace(',', ".")];
+
+        if ambiguous.is_match(&captures[1]) {
+            readings.push(captures[1].replace([',', '.'], ""));
+        }
+
+        let unit = captures[2].to_lowercase();
+        let unit_matches = match &self.unit {
+            Some(_) if unit.is_empty() => !self.require_unit,
+            Some(expected_unit) => &unit == expected_unit,
+            None => unit.is_empty(),
+        };
+
+        unit_matches
+            && readings
+                .iter()
+                .filter_map(|reading| reading.parse::<f64>().ok())
+                .any(|value| self.value_matches(value))
+    }
+
+    fn value_matches(&self, value: f64) -> bool {
+        let within_tolerance = match self.value {
+            Some(expected_value) => {
+                let tolerance = self
+                    .tolerance
+                    .max(self.relative_tolerance * expected_value.abs());
+                (value - expected_value).abs() <= tolerance
+            }
+            None => true,
+        };
+
+        within_tolerance
+            && self.min.is_none_or(|min| value >= min)
+            && self.max.is_none_or(|max| value <= max)
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum CompiledEntryMatch {
     Id { id: Vec<usize> },
     Content { content: Vec<Regex> },
+    Pairs { pairs: Vec<(usize, usize)> },
+    Numeric { numeric: Vec<CompiledNumericMatch> },
 }
 
-#[derive(Debug, Getters)]
+impl CompiledEntryMatch {
+    pub fn len(&self) -> usize {
+        match self {
+            CompiledEntryMatch::Id { id } => id.len(),
+            CompiledEntryMatch::Content { content } => content.len(),
+            CompiledEntryMatch::Pairs { pairs } => pairs.len(),
+            CompiledEntryMatch::Numeric { numeric } => numeric.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Debug, Clone, Getters)]
 pub struct QuestionStore {
     id: usize,
     title: Option<String>,
     content: String,
     mode: QuestionMode,
+    feedback: Option<FeedbackInput>,
+    explanation: Option<String>,
+    hints: Vec<HintInput>,
+    tags: Vec<String>,
+    difficulty: Option<usize>,
+    time_limit: Option<u64>,
+    branches: Vec<BranchRuleInput>,
     optional: bool,
+    points: Option<f64>,
+    scoring_policy: ScoringPolicy,
+    wrong_entry_penalty: Option<f64>,
     min_entries: Option<usize>,
     max_entries: Option<usize>,
     min_correct_entries: Option<usize>,
     max_wrong_entries: Option<usize>,
     correct_entry_match: Option<CompiledEntryMatch>,
+    shuffle_answers: bool,
     answer_ids: Vec<usize>,
     answers: HashMap<usize, AnswerStore>,
+    match_answer_ids: Vec<usize>,
+    match_answers: HashMap<usize, AnswerStore>,
+    blank_ids: Vec<usize>,
+    blanks: HashMap<usize, BlankStore>,
 }
 
-#[derive(Debug, Getters)]
+#[derive(Debug, Clone, Getters)]
+pub struct BlankStore {
+    id: usize,
+    correct_entry_match: Option<CompiledEntryMatch>,
+    answer_ids: Vec<usize>,
+    answers: HashMap<usize, AnswerStore>,
+}
+
+#[derive(Debug, Clone, Getters)]
 pub struct SectionStore {
     id: usize,
     title: Option<String>,
     description: Option<String>,
+    mode: Option<QuizMode>,
+    thresholds: ThresholdsInput,
+    time_limit: Option<u64>,
+    shuffle_questions: bool,
+    pool: Option<PoolInput>,
+    branches: Vec<BranchRuleInput>,
     question_ids: Vec<usize>,
 }
 
-#[derive(Debug, Getters)]
+#[derive(Debug, Clone, Getters)]
 pub struct QuizStore {
     uid: String,
     version: usize,
     title: Option<String>,
     description: Option<String>,
     quiz_mode: QuizMode,
+    feedback_reveal: FeedbackReveal,
     block_answer_updates_for: Option<Vec<QuestionStatusInput>>,
-    min_answered_questions: Option<usize>,
-    max_answered_questions: Option<usize>,
-    min_correct_questions: Option<usize>,
-    max_wrong_questions: Option<usize>,
+    thresholds: ThresholdsInput,
+    time_limit: Option<u64>,
+    max_attempts: Option<usize>,
+    attempt_scoring: AttemptScoring,
+    shuffle_sections: bool,
     section_ids: Vec<usize>,
     sections: HashMap<usize, SectionStore>,
     question_ids: Vec<usize>,
     questions: HashMap<usize, QuestionStore>,
 }
 
+impl QuestionStore {
+    /// Answer level points take precedence over question status when any answer defines them.
+    pub fn has_answer_points(&self) -> bool {
+        self.answers.values().any(|answer| answer.points.is_some())
+    }
+
+    /// Number of distinct correct entries needed for full credit.
+    pub fn required_correct_entries(&self) -> usize {
+        match (self.min_correct_entries, &self.correct_entry_match) {
+            (Some(min_correct_entries), _) => min_correct_entries,
+            (None, _) if self.mode == QuestionMode::Cloze => self.blank_ids.len(),
+            (None, Some(entry_match)) => entry_match.len(),
+            (None, None) => 0,
+        }
+    }
+
+    pub fn hint_cost(&self, revealed_hints: usize) -> f64 {
+        self.hints
+            .iter()
+            .take(revealed_hints)
+            .filter_map(|hint| *hint.cost())
+            .sum()
+    }
+
+    /// Maximum score a question can yield. Defaults to a single point for unweighted questions.
+    pub fn max_points(&self) -> f64 {
+        max_points(
+            self.points,
+            self.answers.values().map(|answer| answer.points),
+        )
+    }
+
+    pub fn is_eligible_for(&self, pool: &PoolInput) -> bool {
+        pool.accepts(&self.tags, self.difficulty)
+    }
+
+    /// Boolean answers keep their fixed true/false layout and free text modes have nothing to reorder.
+    fn shuffle(&mut self, rng: &mut Rng) {
+        if !self.shuffle_answers || self.mode == QuestionMode::Boolean {
+            return;
+        }
+
+        rng.shuffle(&mut self.answer_ids);
+        rng.shuffle(&mut self.match_answer_ids);
+
+        for blank_id in self.blank_ids.iter() {
+            if let Some(blank) = self.blanks.get_mut(blank_id) {
+                rng.shuffle(&mut blank.answer_ids);
+            }
+        }
+    }
+
+    /// Boolean questions always carry the same two answers, labelled per question or else per
+    /// quiz. Expanded once the labels are resolved, see `QuizStore::try_from`.
+    fn expand_boolean(&mut self, labels: &BooleanLabels, correct: Option<bool>) {
+        self.answer_ids = vec![BOOLEAN_TRUE_ANSWER_ID, BOOLEAN_FALSE_ANSWER_ID];
+        self.answers = HashMap::from([
+            (
+                BOOLEAN_TRUE_ANSWER_ID,
+                AnswerStore {
+                    id: BOOLEAN_TRUE_ANSWER_ID,
+                    content: labels.true_label().clone(),
+                    points: None,
+                    feedback: None,
+                },
+            ),
+            (
+                BOOLEAN_FALSE_ANSWER_ID,
+                AnswerStore {
+                    id: BOOLEAN_FALSE_ANSWER_ID,
+                    content: labels.false_label().clone(),
+                    points: None,
+                    feedback: None,
+                },
+            ),
+        ]);
+        self.max_entries = Some(1);
+        self.correct_entry_match = correct.map(|correct| CompiledEntryMatch::Id {
+            id: vec![if correct {
+                BOOLEAN_TRUE_ANSWER_ID
+            } else {
+                BOOLEAN_FALSE_ANSWER_ID
+            }],
+        });
+    }
+}
+
+impl QuizStore {
+    pub fn max_score(&self) -> f64 {
+        self.questions
+            .values()
+            .map(|question| question.max_points())
+            .sum()
+    }
+
+    pub fn has_time_limits(&self) -> bool {
+        self.time_limit.is_some()
+            || self
+                .sections
+                .values()
+                .any(|section| section.time_limit.is_some())
+            || self
+                .questions
+                .values()
+                .any(|question| question.time_limit.is_some())
+    }
+
+    pub fn section_of(&self, question_id: usize) -> Option<&SectionStore> {
+        self.section_ids
+            .iter()
+            .filter_map(|section_id| self.sections.get(section_id))
+            .find(|section| section.question_ids.contains(&question_id))
+    }
+
+    /// Draws pooled questions, then reorders sections, questions and answers wherever shuffling
+    /// is enabled. Questions left out of a draw are dropped, so state, thresholds and views only
+    /// ever see the drawn ones. The quiz wide question order follows the shuffled sections so
+    /// sequential navigation matches the layout.
+    pub fn randomize(&mut self, seed: u64) {
+        let mut rng = Rng::new(seed);
+
+        for section_id in self.section_ids.iter() {
+            if let Some(section) = self.sections.get_mut(section_id) {
+                section.draw(&self.questions, &mut rng);
+            }
+        }
+
+        if self.shuffle_sections {
+            rng.shuffle(&mut self.section_ids);
+        }
+
+        let mut question_ids = Vec::new();
+
+        for section_id in self.section_ids.iter() {
+            if let Some(section) = self.sections.get_mut(section_id) {
+                if section.shuffle_questions {
+                    rng.shuffle(&mut section.question_ids);
+                }
+
+                question_ids.extend(section.question_ids.iter().copied());
+            }
+        }
+
+        for question_id in question_ids.iter() {
+            if let Some(question) = self.questions.get_mut(question_id) {
+                question.shuffle(&mut rng);
+            }
+        }
+
+        self.questions
+            .retain(|question_id, _| question_ids.contains(question_id));
+        self.question_ids = question_ids;
+    }
+}
+
+impl SectionStore {
+    fn eligible_question_ids(&self, questions: &HashMap<usize, QuestionStore>) -> Vec<usize> {
+        match &self.pool {
+            Some(pool) => self
+                .question_ids
+                .iter()
+                .filter(|question_id| match questions.get(question_id) {
+                    Some(question) => question.is_eligible_for(pool),
+                    None => false,
+                })
+                .copied()
+                .collect(),
+            None => self.question_ids.clone(),
+        }
+    }
+
+    /// Keeps `count` randomly drawn eligible questions in their authored order.
+    fn draw(&mut self, questions: &HashMap<usize, QuestionStore>, rng: &mut Rng) {
+        let count = match &self.pool {
+            Some(pool) => *pool.count(),
+            None => return,
+        };
+
+        let mut drawn_ids = self.eligible_question_ids(questions);
+        rng.shuffle(&mut drawn_ids);
+        drawn_ids.truncate(count);
+
+        self.question_ids
+            .retain(|question_id| drawn_ids.contains(question_id));
+    }
+}
+
 impl From<&SectionInput> for SectionStore {
     fn from(section: &SectionInput) -> Self {
         Self {
             id: *section.id(),
             title: section.title().clone(),
             description: section.description().clone(),
+            mode: section.mode().clone(),
+            thresholds: section.thresholds().clone(),
+            time_limit: *section.time_limit(),
+            shuffle_questions: section.shuffle_questions().unwrap_or(false),
+            pool: section.pool().clone(),
+            branches: section.branches().clone(),
             question_ids: section
                 .questions()
                 .iter()
@@ -113,6 +625,8 @@ impl From<&AnswerInput> for AnswerStore {
         Self {
             id: *answer.id(),
             content: answer.content().clone(),
+            points: *answer.points(),
+            feedback: answer.feedback().clone(),
         }
     }
 }
@@ -129,16 +643,67 @@ impl TryFrom<&EntryMatch> for CompiledEntryMatch {
                     .map(|m| Regex::new(&format!("(?i){}", m)))
                     .collect::<Result<Vec<Regex>, regex::Error>>()?,
             },
+            EntryMatch::Pairs { pairs } => CompiledEntryMatch::Pairs {
+                pairs: pairs.clone(),
+            },
+            EntryMatch::Numeric { numeric } => CompiledEntryMatch::Numeric {
+                numeric: numeric.iter().map(CompiledNumericMatch::from).collect(),
+            },
         };
 
         Ok(compiled_entry_match)
     }
 }
 
+impl BlankStore {
+    fn try_new(question_id: usize, blank: &BlankInput) -> StoreResult<Self> {
+        let mut answer_ids = Vec::new();
+        let mut answers = HashMap::new();
+
+        if let Some(blank_answers) = blank.answers() {
+            for answer in blank_answers {
+                if answers.contains_key(answer.id()) {
+                    return Err(StoreError {
+                        error: StoreErrorEnum::DuplicateAnswerId {
+                            question_id,
+                            answer_id: *answer.id(),
+                        },
+                    });
+                }
+
+                answer_ids.push(*answer.id());
+                answers.insert(*answer.id(), AnswerStore::from(answer));
+            }
+        }
+
+        Ok(Self {
+            id: *blank.id(),
+            correct_entry_match: match blank.correct_entry_match() {
+                Some(correct_entry_match) => Some(correct_entry_match.try_into()?),
+                None => None,
+            },
+            answer_ids,
+            answers,
+        })
+    }
+}
+
 impl TryFrom<&QuestionInput> for QuestionStore {
     type Error = StoreError;
 
+    /// Boolean questions are left for `QuizStore::try_from` to expand, which knows the quiz wide
+    /// labels. Authored answers would silently be replaced there, so they are rejected here.
     fn try_from(question: &QuestionInput) -> StoreResult<Self> {
+        if question.mode() == &QuestionMode::Boolean
+            && (question.answers().is_some() || question.correct_entry_match().is_some())
+        {
+            return Err(StoreError {
+                error: StoreErrorEnum::BooleanAnswersNotAllowed {
+                    question_id: *question.id(),
+                },
+            });
+        }
+
         let mut answer_ids = Vec::new();
         let mut answers = HashMap::new();
 
@@ -161,12 +726,103 @@ impl TryFrom<&QuestionInput> for QuestionStore {
             None => {}
         }
 
-        Ok(QuestionStore {
+        let mut match_answer_ids = Vec::new();
+        let mut match_answers = HashMap::new();
+
+        if let Some(question_match_answers) = question.match_answers() {
+            for answer in question_match_answers {
+                if match_answers.contains_key(answer.id()) {
+                    return Err(StoreError {
+                        error: StoreErrorEnum::DuplicateAnswerId {
+                            question_id: *question.id(),
+                            answer_id: *answer.id(),
+                        },
+                    });
+                }
+
+                match_answer_ids.push(*answer.id());
+                match_answers.insert(*answer.id(), AnswerStore::from(answer));
+            }
+        }
+
+        let mut blank_ids = Vec::new();
+        let mut blanks = HashMap::new();
+
+        if let Some(question_blanks) = question.blanks() {
+            for blank in question_blanks {
+                if blanks.contains_key(blank.id()) {
+                    return Err(StoreError {
+                        error: StoreErrorEnum::DuplicateBlankId {
+                            question_id: *question.id(),
+                            blank_id: *blank.id(),
+                        },
+                    });
+                }
+
+                blank_ids.push(*blank.id());
+                blanks.insert(*blank.id(), BlankStore::try_new(*question.id(), blank)?);
+            }
+        }
+
+        let placeholders = placeholder_ids(question.content());
+
+        for blank_id in placeholders.iter() {
+            if !blanks.contains_key(blank_id) {
+                return Err(StoreError {
+                    error: StoreErrorEnum::BlankNotFound {
+                        question_id: *question.id(),
+                        blank_id: *blank_id,
+                    },
+                });
+            }
+        }
+
+        // A blank without a placeholder is never shown, so it could never be filled in.
+        if question.mode() == &QuestionMode::Cloze {
+            if let Some(blank_id) = blank_ids
+                .iter()
+                .find(|blank_id| !placeholders.contains(blank_id))
+            {
+                return Err(StoreError {
+                    error: StoreErrorEnum::PlaceholderNotFound {
+                        question_id: *question.id(),
+                        blank_id: *blank_id,
+                    },
+                });
+            }
+        }
+
+        if let Some(EntryMatch::Pairs { pairs }) = question.correct_entry_match() {
+            for (answer_id, match_answer_id) in pairs {
+                if !answers.contains_key(answer_id) || !match_answers.contains_key(match_answer_id)
+                {
+                    return Err(StoreError {
+                        error: StoreErrorEnum::PairAnswerNotFound {
+                            question_id: *question.id(),
+                            answer_id: *answer_id,
+                            match_answer_id: *match_answer_id,
+                        },
+                    });
+                }
+            }
+        }
+
+        let question_store = QuestionStore {
             id: *question.id(),
             title: question.title().clone(),
             content: question.content().clone(),
             mode: question.mode().clone(),
+            feedback: question.feedback().clone(),
+            explanation: question.explanation().clone(),
+            hints: question.hints().clone().unwrap_or_default(),
+            tags: question.tags().clone(),
+            difficulty: *question.difficulty(),
+            time_limit: *question.time_limit(),
+            branches: question.branches().clone(),
             optional: question.optional().clone(),
+            points: *question.points(),
+            scoring_policy: question.scoring_policy().clone(),
+            wrong_entry_penalty: *question.wrong_entry_penalty(),
             min_entries: *question.min_entries(),
             max_entries: *question.max_entries(),
             min_correct_entries: *question.min_correct_entries(),
@@ -175,12 +831,111 @@ impl TryFrom<&QuestionInput> for QuestionStore {
                 Some(correct_entry_match) => Some(correct_entry_match.try_into()?),
                 None => None,
             },
+            shuffle_answers: question.shuffle_answers().unwrap_or(false),
             answer_ids,
             answers,
-        })
+            match_answer_ids,
+            match_answers,
+            blank_ids,
+            blanks,
+        };
+
+        Ok(question_store)
     }
 }
 
+/// Blank placeholders are written as `{{<blank id>}}` inside the question content.
+pub fn placeholder_ids(content: &str) -> Vec<usize> {
+    static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+
+    PLACEHOLDER
+        .get_or_init(|| Regex::new(r"\{\{\s*(\d+)\s*\}\}").unwrap())
+        .captures_iter(content)
+        .filter_map(|captures| captures[1].parse::<usize>().ok())
+        .collect()
+}
+
+/// Branches only jump forward, so a rule must not go to a question at or before the one it is
+/// evaluated after: the question itself, or the last question of the section for section rules.
+fn validate_branch_rule(
+    rule: &BranchRuleInput,
+    from_question_id: Option<usize>,
+    question_ids: &[usize],
+    sections: &HashMap<usize, SectionStore>,
+    questions: &HashMap<usize, QuestionStore>,
+) -> StoreResult<()> {
+    let question_not_found = |question_id: usize| StoreError {
+        error: StoreErrorEnum::BranchTargetNotFound {
+            question_id: Some(question_id),
+            section_id: None,
+        },
+    };
+    let section_not_found = |section_id: usize| StoreError {
+        error: StoreErrorEnum::BranchTargetNotFound {
+            question_id: None,
+            section_id: Some(section_id),
+        },
+    };
+
+    let position = |question_id: &usize| question_ids.iter().position(|id| id == question_id);
+    let from_index = from_question_id.as_ref().and_then(position);
+    let is_backward = |question_id: &usize| match (position(question_id), from_index) {
+        (Some(index), Some(from_index)) => index <= from_index,
+        _ => false,
+    };
+
+    if !questions.contains_key(rule.when().question_id()) {
+        return Err(question_not_found(*rule.when().question_id()));
+    }
+
+    for action in rule.then().iter().chain(rule.otherwise().iter()) {
+        for question_id in action
+            .go_to_question()
+            .iter()
+            .chain(action.skip_questions().iter())
+        {
+            if !questions.contains_key(question_id) {
+                return Err(question_not_found(*question_id));
+            }
+        }
+
+        for section_id in action
+            .go_to_section()
+            .iter()
+            .chain(action.skip_sections().iter())
+        {
+            if !sections.contains_key(section_id) {
+                return Err(section_not_found(*section_id));
+            }
+        }
+
+        if let Some(question_id) = action.go_to_question() {
+            if is_backward(question_id) {
+                return Err(StoreError {
+                    error: StoreErrorEnum::BackwardBranchTarget {
+                        question_id: Some(*question_id),
+                        section_id: None,
+                    },
+                });
+            }
+        }
+
+        if let Some(section_id) = action.go_to_section() {
+            let first_question_id = sections[section_id].question_ids().first();
+            if first_question_id.is_some_and(is_backward) {
+                return Err(StoreError {
+                    error: StoreErrorEnum::BackwardBranchTarget {
+                        question_id: None,
+                        section_id: Some(*section_id),
+                    },
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
 impl TryFrom<&QuizInput> for QuizStore {
     type Error = StoreError;
 
@@ -200,7 +955,11 @@ impl TryFrom<&QuizInput> for QuizStore {
             }
 
             section_ids.push(*section.id());
-            sections.insert(*section.id(), SectionStore::from(section));
+            let mut section_store = SectionStore::from(section);
+            section_store.shuffle_questions = section
+                .shuffle_questions()
+                .unwrap_or(*quiz.shuffle_questions());
+            sections.insert(*section.id(), section_store);
 
             for question in section.questions() {
                 if questions.contains_key(question.id()) {
@@ -213,7 +972,64 @@ impl TryFrom<&QuizInput> for QuizStore {
                 }
 
                 question_ids.push(*question.id());
-                questions.insert(*question.id(), QuestionStore::try_from(question)?);
+                let mut question_store = QuestionStore::try_from(question)?;
+                question_store.wrong_entry_penalty = question_store
+                    .wrong_entry_penalty
+                    .or(*quiz.wrong_entry_penalty());
+                question_store.shuffle_answers = question
+                    .shuffle_answers()
+                    .or(*section.shuffle_answers())
+                    .unwrap_or(*quiz.shuffle_answers());
+
+                if question.mode() == &QuestionMode::Boolean {
+                    let labels = question
+                        .boolean_labels()
+                        .as_ref()
+                        .or(quiz.boolean_labels().as_ref())
+                        .cloned()
+                        .unwrap_or_default();
+                    question_store.expand_boolean(&labels, *question.correct());
+                }
+
+                questions.insert(*question.id(), question_store);
+            }
+
+            if let Some(section_store) = sections.get(section.id()) {
+                if let Some(pool) = section_store.pool() {
+                    let available = section_store.eligible_question_ids(&questions).len();
+
+                    if available < *pool.count() {
+                        return Err(StoreError {
+                            error: StoreErrorEnum::PoolTooSmall {
+                                section_id: *section.id(),
+                                count: *pool.count(),
+                                available,
+                            },
+                        });
+                    }
+                }
+            }
+        }
+
+        for section_id in &section_ids {
+            let section = &sections[section_id];
+
+            for question_id in section.question_ids() {
+                for rule in questions[question_id].branches() {
+                    let from_question_id = Some(*question_id);
+                    validate_branch_rule(
+                        rule,
+                        from_question_id,
+                        &question_ids,
+                        &sections,
+                        &questions,
+                    )?;
+                }
+            }
+
+            let last_question_id = section.question_ids().last().copied();
+            for rule in section.branches() {
+                validate_branch_rule(rule, last_question_id, &question_ids, &sections, &questions)?;
             }
         }
 
@@ -223,11 +1039,13 @@ impl TryFrom<&QuizInput> for QuizStore {
             title: quiz.title().clone(),
             description: quiz.description().clone(),
             quiz_mode: quiz.mode().clone(),
+            feedback_reveal: quiz.feedback_reveal().clone(),
             block_answer_updates_for: quiz.block_answer_updates_for().clone(),
-            max_answered_questions: quiz.max_answered_questions().clone(),
-            min_answered_questions: quiz.min_answered_questions().clone(),
-            min_correct_questions: quiz.min_correct_questions().clone(),
-            max_wrong_questions: quiz.max_wrong_questions().clone(),
+            thresholds: quiz.thresholds().clone(),
+            time_limit: *quiz.time_limit(),
+            max_attempts: *quiz.max_attempts(),
+            attempt_scoring: quiz.attempt_scoring().clone(),
+            shuffle_sections: *quiz.shuffle_sections(),
             section_ids,
             sections,
             question_ids,
@@ -235,3 +1053,306 @@ impl TryFrom<&QuizInput> for QuizStore {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::{RunnerError, RunnerErrorEnum};
+    use crate::state::{StateError, StateErrorEnum};
+
+    #[test]
+    fn it_rejects_pairs_referencing_unknown_answers() {
+        let input_json = include_str!("../tests/input/matching_quiz.json")
+            .replace("[[1, 3], [2, 1], [3, 2]]", "[[1, 3], [2, 1], [3, 4]]");
+        let quiz_input = serde_json::from_str::<QuizInput>(&input_json).unwrap();
+
+        assert!(matches!(
+            QuizStore::try_from(&quiz_input),
+            Err(StoreError {
+                error: StoreErrorEnum::PairAnswerNotFound {
+                    question_id: 1,
+                    answer_id: 3,
+                    match_answer_id: 4,
+                }
+            })
+        ));
+    }
+
+    #[test]
+    fn it_matches_numeric_entries_with_tolerance_and_units() {
+        let numeric_match = CompiledNumericMatch::from(
+            &serde_json::from_str::<NumericMatch>(
+                r#"{ "value": 3.14, "tolerance": 0.005, "unit": "cm" }"#,
+            )
+            .unwrap(),
+        );
+
+        assert!(numeric_match.is_match("3.14"));
+        assert!(numeric_match.is_match("3.140"));
+        assert!(numeric_match.is_match(" 3,14 "));
+        assert!(numeric_match.is_match("3,1400"));
+        assert!(numeric_match.is_match("3.1445 CM"));
+        assert!(!numeric_match.is_match("3.15"));
+        assert!(!numeric_match.is_match("3.14 kg"));
+        assert!(!numeric_match.is_match("pi"));
+
+        let range_match = CompiledNumericMatch::from(
+            &serde_json::from_str::<NumericMatch>(
+                r#"{ "min": 10, "max": 20, "requireUnit": true, "unit": "kg" }"#,
+            )
+            .unwrap(),
+        );
+
+        assert!(range_match.is_match("1.5e1 kg"));
+        assert!(!range_match.is_match("15"));
+        assert!(!range_match.is_match("21kg"));
+    }
+
+    #[test]
+    fn it_reads_ambiguous_separators_either_way() {
+        let numeric_match = CompiledNumericMatch::from(
+            &serde_json::from_str::<NumericMatch>(r#"{ "value": 1000 }"#).unwrap(),
+        );
+
+        assert!(numeric_match.is_match("1,000"));
+        assert!(numeric_match.is_match("1.000"));
+        assert!(numeric_match.is_match("1000"));
+        assert!(!numeric_match.is_match("1,0"));
+        assert!(!numeric_match.is_match("1,0000"));
+
+        let range_match = CompiledNumericMatch::from(
+            &serde_json::from_str::<NumericMatch>(r#"{ "min": 0, "max": 2 }"#).unwrap(),
+        );
+
+        assert!(range_match.is_match("1,500"));
+        assert!(range_match.is_match("0,125"));
+        assert!(!range_match.is_match("-12,500"));
+        assert!(!range_match.is_match("1,2500e1"));
+    }
+
+    #[test]
+    fn it_rejects_authored_answers_on_boolean_questions() {
+        let input_json = include_str!("../tests/input/boolean_quiz.json").replacen(
+            "\"mode\": \"boolean\",",
+            "\"mode\": \"boolean\", \"answers\": [{ \"id\": 5, \"content\": \"Maybe\" }],",
+            1,
+        );
+        let quiz_input = serde_json::from_str::<QuizInput>(&input_json).unwrap();
+
+        assert!(matches!(
+            QuizStore::try_from(&quiz_input),
+            Err(StoreError {
+                error: StoreErrorEnum::BooleanAnswersNotAllowed { .. }
+            })
+        ));
+    }
+
+    #[test]
+    fn it_rejects_blanks_without_placeholders() {
+        let input_json = include_str!("../tests/input/cloze_quiz.json")
+            .replace("orbiting the {{3}}", "orbiting the Sun");
+        let quiz_input = serde_json::from_str::<QuizInput>(&input_json).unwrap();
+
+        assert!(matches!(
+            QuizStore::try_from(&quiz_input),
+            Err(StoreError {
+                error: StoreErrorEnum::PlaceholderNotFound {
+                    question_id: 1,
+                    blank_id: 3,
+                }
+            })
+        ));
+    }
+
+    #[test]
+    fn it_rejects_placeholders_without_blanks() {
+        let input_json = include_str!("../tests/input/cloze_quiz.json")
+            .replace("orbiting the {{3}}", "orbiting the {{4}}");
+        let quiz_input = serde_json::from_str::<QuizInput>(&input_json).unwrap();
+
+        assert!(matches!(
+            QuizStore::try_from(&quiz_input),
+            Err(StoreError {
+                error: StoreErrorEnum::BlankNotFound {
+                    question_id: 1,
+                    blank_id: 4,
+                }
+            })
+        ));
+    }
+
+    #[test]
+    fn it_rejects_branches_that_go_backward() {
+        let input_json = include_str!("../tests/input/branching_quiz.json")
+            .replace("\"goToSection\": 3", "\"goToSection\": 1");
+        let quiz_input = serde_json::from_str::<QuizInput>(&input_json).unwrap();
+
+        assert!(matches!(
+            QuizStore::try_from(&quiz_input),
+            Err(StoreError {
+                error: StoreErrorEnum::BackwardBranchTarget {
+                    question_id: None,
+                    section_id: Some(1),
+                }
+            })
+        ));
+
+        let input_json = include_str!("../tests/input/branching_quiz.json")
+            .replace("\"goToSection\": 3", "\"goToQuestion\": 1");
+        let quiz_input = serde_json::from_str::<QuizInput>(&input_json).unwrap();
+
+        assert!(matches!(
+            QuizStore::try_from(&quiz_input),
+            Err(StoreError {
+                error: StoreErrorEnum::BackwardBranchTarget {
+                    question_id: Some(1),
+                    section_id: None,
+                }
+            })
+        ));
+    }
+
+    #[test]
+    fn it_rejects_pools_larger_than_eligible_questions() {
+        let input_json =
+            include_str!("../tests/input/pool_quiz.json").replace("\"count\": 3", "\"count\": 5");
+        let quiz_input = serde_json::from_str::<QuizInput>(&input_json).unwrap();
+
+        assert!(matches!(
+            QuizStore::try_from(&quiz_input),
+            Err(StoreError {
+                error: StoreErrorEnum::PoolTooSmall {
+                    section_id: 1,
+                    count: 5,
+                    available: 4,
+                }
+            })
+        ));
+    }
+
+    #[test]
+    fn it_serializes_the_code_of_every_error() {
+        let store_errors = vec![
+            StoreErrorEnum::DuplicateSectionId { section_id: 1 },
+            StoreErrorEnum::DuplicateQuestionId {
+                section_id: 1,
+                question_id: 1,
+            },
+            StoreErrorEnum::DuplicateAnswerId {
+                question_id: 1,
+                answer_id: 1,
+            },
+            StoreErrorEnum::DuplicateBlankId {
+                question_id: 1,
+                blank_id: 1,
+            },
+            StoreErrorEnum::BlankNotFound {
+                question_id: 1,
+                blank_id: 1,
+            },
+            StoreErrorEnum::PlaceholderNotFound {
+                question_id: 1,
+                blank_id: 1,
+            },
+            StoreErrorEnum::PairAnswerNotFound {
+                question_id: 1,
+                answer_id: 1,
+                match_answer_id: 1,
+            },
+            StoreErrorEnum::BranchTargetNotFound {
+                question_id: Some(1),
+                section_id: None,
+            },
+            StoreErrorEnum::BackwardBranchTarget {
+                question_id: None,
+                section_id: Some(1),
+            },
+            StoreErrorEnum::PoolTooSmall {
+                section_id: 1,
+                count: 2,
+                available: 1,
+            },
+            StoreErrorEnum::BooleanAnswersNotAllowed { question_id: 1 },
+        ];
+        let state_errors = vec![
+            StateErrorEnum::SectionNotFound { section_id: 1 },
+            StateErrorEnum::QuestionNotFound { question_id: 1 },
+            StateErrorEnum::QuestionSkipped { question_id: 1 },
+            StateErrorEnum::TimeLimitExceeded {
+                question_id: None,
+                deadline: 1,
+            },
+            StateErrorEnum::AnswerNotFound {
+                question_id: 1,
+                answer_id: 1,
+            },
+            StateErrorEnum::MatchAnswerNotFound {
+                question_id: 1,
+                match_answer_id: 1,
+            },
+            StateErrorEnum::BlankNotFound {
+                question_id: 1,
+                blank_id: 1,
+            },
+            StateErrorEnum::QuestionNotAvailable { question_id: 1 },
+            StateErrorEnum::QuestionHasNoSelectableAnswers { question_id: 1 },
+            StateErrorEnum::QuestionCanNotBeUpdated { question_id: 1 },
+            StateErrorEnum::QuestionModeMismatch { question_id: 1 },
+            StateErrorEnum::QuestionCanNotBeGraded { question_id: 1 },
+            StateErrorEnum::GradePointsOutOfRange {
+                question_id: 1,
+                points: 1.0,
+            },
+            StateErrorEnum::NoHintsLeft { question_id: 1 },
+            StateErrorEnum::AnswerSelectionMismatch {
+                question_id: 1,
+                answer_ids: vec![1],
+            },
+            StateErrorEnum::QuizFinished,
+            StateErrorEnum::QuizAlreadyStarted,
+            StateErrorEnum::QuizSubmitted,
+            StateErrorEnum::AttemptInProgress,
+            StateErrorEnum::NoAttemptsLeft { max_attempts: 1 },
+        ];
+        let runner_errors = vec![
+            RunnerErrorEnum::InputError {
+                source: serde_json::from_str::<QuizInput>("{").unwrap_err(),
+            },
+            RunnerErrorEnum::StoreError {
+                source: StoreError {
+                    error: StoreErrorEnum::DuplicateSectionId { section_id: 1 },
+                },
+            },
+            RunnerErrorEnum::StateError {
+                source: StateError {
+                    error: StateErrorEnum::QuizFinished,
+                },
+            },
+            RunnerErrorEnum::NothingToUndo,
+            RunnerErrorEnum::NothingToRedo,
+            RunnerErrorEnum::GenerationOutOfRange { generation: 1 },
+        ];
+
+        let input_json = include_str!("../tests/input/hints_quiz.json").replace("^Paris$", "(");
+        let quiz_input = serde_json::from_str::<QuizInput>(&input_json).unwrap();
+        let regex_error = QuizStore::try_from(&quiz_input).unwrap_err();
+
+        let codes = store_errors
+            .into_iter()
+            .map(|error| StoreError { error })
+            .chain(std::iter::once(regex_error))
+            .map(|error| (serde_json::to_value(&error).unwrap(), error.code()))
+            .chain(state_errors.into_iter().map(|error| {
+                let error = StateError { error };
+                (serde_json::to_value(&error).unwrap(), error.code())
+            }))
+            .chain(runner_errors.into_iter().map(|error| {
+                let error = RunnerError { error };
+                (serde_json::to_value(&error).unwrap(), error.code())
+            }));
+
+        for (value, code) in codes {
+            assert_eq!(value["code"], code);
+        }
+    }
+}
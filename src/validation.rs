@@ -0,0 +1,737 @@
+use crate::input::{
+    AnswerInput, BranchActionInput, BranchRuleInput, EntryMatch, QuestionInput, QuestionMode,
+    QuizInput, SectionInput, ThresholdsInput,
+};
+use crate::store::placeholder_ids;
+use derive_getters::Getters;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Problem in a quiz definition. `pointer` is a JSON pointer into the input document and `code`
+/// a stable machine-readable identifier.
+#[derive(Serialize, Debug, Clone, Getters)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    severity: Severity,
+    pointer: String,
+    #[getter(skip)]
+    code: &'static str,
+    message: String,
+}
+
+impl Diagnostic {
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+}
+
+/// Checks a quiz definition without building it and reports every problem instead of stopping at
+/// the first one. Errors make the quiz unusable, warnings flag definitions that most likely do
+/// not behave as intended.
+pub fn validate(input: &str) -> Vec<Diagnostic> {
+    match serde_json::from_str::<QuizInput>(input) {
+        Ok(quiz) => validate_quiz(&quiz),
+        Err(error) => vec![Diagnostic {
+            severity: Severity::Error,
+            pointer: String::new(),
+            code: "invalidJson",
+            message: error.to_string(),
+        }],
+    }
+}
+
+pub fn validate_quiz(quiz: &QuizInput) -> Vec<Diagnostic> {
+    let mut validator = Validator::default();
+    validator.validate_quiz(quiz);
+    validator.diagnostics
+}
+
+#[derive(Default)]
+struct Validator {
+    diagnostics: Vec<Diagnostic>,
+    section_ids: HashSet<usize>,
+    question_ids: HashSet<usize>,
+    question_order: Vec<usize>,
+    section_question_ids: HashMap<usize, Vec<usize>>,
+}
+
+impl Validator {
+    fn error(&mut self, pointer: String, code: &'static str, message: String) {
+        self.diagnostics.push(Diagnostic {
+            severity: Severity::Error,
+            pointer,
+            code,
+            message,
+        });
+    }
+
+    fn warning(&mut self, pointer: String, code: &'static str, message: String) {
+        self.diagnostics.push(Diagnostic {
+            severity: Severity::Warning,
+            pointer,
+            code,
+            message,
+        });
+    }
+
+    fn validate_quiz(&mut self, quiz: &QuizInput) {
+        let mut questions_count = 0;
+        let mut max_score = 0.0;
+
+        for (section_index, section) in quiz.sections().iter().enumerate() {
+            let pointer = format!("/sections/{section_index}");
+
+            if !self.section_ids.insert(*section.id()) {
+                self.error(
+                    format!("{pointer}/id"),
+                    "duplicateSectionId",
+                    format!("section {} is defined more than once", section.id()),
+                );
+            }
+
+            let question_ids = section.questions().iter().map(|question| *question.id());
+            self.section_question_ids
+                .entry(*section.id())
+                .or_insert_with(|| question_ids.collect());
+
+            for (question_index, question) in section.questions().iter().enumerate() {
+                let pointer = format!("{pointer}/questions/{question_index}");
+
+                self.question_order.push(*question.id());
+                if !self.question_ids.insert(*question.id()) {
+                    self.error(
+                        format!("{pointer}/id"),
+                        "duplicateQuestionId",
+                        format!("question {} is defined more than once", question.id()),
+                    );
+                }
+
+                self.validate_question(&pointer, question);
+            }
+
+            let (section_questions_count, section_max_score) =
+                self.validate_section(&pointer, section);
+            questions_count += section_questions_count;
+            max_score += section_max_score;
+        }
+
+        for (section_index, section) in quiz.sections().iter().enumerate() {
+            let pointer = format!("/sections/{section_index}");
+            let last_question_id = section.questions().last().map(|question| *question.id());
+            self.validate_branches(&pointer, section.branches(), last_question_id);
+
+            for (question_index, question) in section.questions().iter().enumerate() {
+                let pointer = format!("{pointer}/questions/{question_index}");
+                self.validate_branches(&pointer, question.branches(), Some(*question.id()));
+            }
+        }
+
+        self.validate_thresholds("", quiz.thresholds(), questions_count, max_score);
+
+        if *quiz.max_attempts() == Some(0) {
+            self.error(
+                "/maxAttempts".into(),
+                "noAttemptsAllowed",
+                "quiz allows no attempts at all".into(),
+            );
+        }
+    }
+
+    /// Returns how many questions the section presents and the highest score they can yield.
+    fn validate_section(&mut self, pointer: &str, section: &SectionInput) -> (usize, f64) {
+        let mut points: Vec<f64> = section
+            .questions()
+            .iter()
+            .map(QuestionInput::max_points)
+            .collect();
+        let mut questions_count = section.questions().len();
+
+        if let Some(pool) = section.pool() {
+            let available = section
+                .questions()
+                .iter()
+                .filter(|question| pool.accepts(question.tags(), *question.difficulty()))
+                .count();
+
+            if available < *pool.count() {
+                self.error(
+                    format!("{pointer}/pool/count"),
+                    "poolTooSmall",
+                    format!(
+                        "section {} draws {} questions but only {available} are eligible",
+                        section.id(),
+                        pool.count()
+                    ),
+                );
+            }
+
+            // Upper bound: the drawn questions can at most be the highest weighted ones.
+            points.sort_by(|a, b| b.total_cmp(a));
+            points.truncate(*pool.count());
+            questions_count = questions_count.min(*pool.count());
+        }
+
+        let max_score = points.iter().sum();
+        self.validate_thresholds(pointer, section.thresholds(), questions_count, max_score);
+
+        (questions_count, max_score)
+    }
+
+    fn validate_thresholds(
+        &mut self,
+        pointer: &str,
+        thresholds: &ThresholdsInput,
+        questions_count: usize,
+        max_score: f64,
+    ) {
+        let counts = [
+            ("minAnsweredQuestions", thresholds.min_answered_questions()),
+            ("minCorrectQuestions", thresholds.min_correct_questions()),
+        ];
+
+        for (field, count) in counts {
+            if let Some(count) = count.filter(|count| *count > questions_count) {
+                self.error(
+                    format!("{pointer}/{field}"),
+                    "unreachableThreshold",
+                    format!("{field} is {count} but only {questions_count} questions are asked"),
+                );
+            }
+        }
+
+        let scores = [
+            ("minScore", thresholds.min_score()),
+            ("passScore", thresholds.pass_score()),
+        ];
+
+        for (field, score) in scores {
+            if let Some(score) = score.filter(|score| *score > max_score) {
+                self.error(
+                    format!("{pointer}/{field}"),
+                    "unreachableThreshold",
+                    format!("{field} is {score} but at most {max_score} points can be scored"),
+                );
+            }
+        }
+
+        if let (Some(min), Some(max)) = (
+            thresholds.min_answered_questions(),
+            thresholds.max_answered_questions(),
+        ) {
+            if min > max {
+                self.error(
+                    format!("{pointer}/minAnsweredQuestions"),
+                    "thresholdConflict",
+                    format!("minAnsweredQuestions {min} exceeds maxAnsweredQuestions {max}"),
+                );
+            }
+        }
+    }
+
+    fn validate_question(&mut self, pointer: &str, question: &QuestionInput) {
+        let answer_ids = self.unique_answer_ids(&format!("{pointer}/answers"), question.answers());
+        let match_answer_ids =
+            self.unique_answer_ids(&format!("{pointer}/matchAnswers"), question.match_answers());
+        let question_id = question.id();
+
+        let needs_answers = matches!(
+            question.mode(),
+            QuestionMode::Select | QuestionMode::Order | QuestionMode::Match
+        );
+
+        if needs_answers && answer_ids.is_empty() {
+            self.error(
+                format!("{pointer}/answers"),
+                "missingAnswers",
+                format!("question {question_id} has no answers to choose from"),
+            );
+        }
+
+        if question.mode() == &QuestionMode::Match && match_answer_ids.is_empty() {
+            self.error(
+                format!("{pointer}/matchAnswers"),
+                "missingMatchAnswers",
+                format!("question {question_id} has no answers to match against"),
+            );
+        }
+
+        if question.mode() == &QuestionMode::Boolean && question.correct().is_none() {
+            self.warning(
+                format!("{pointer}/correct"),
+                "ungradedQuestion",
+                format!("boolean question {question_id} has no correct value"),
+            );
+        }
+
+        if question.mode() == &QuestionMode::Boolean && question.answers().is_some() {
+            self.error(
+                format!("{pointer}/answers"),
+                "booleanAnswersNotAllowed",
+                format!("boolean question {question_id} takes `correct` instead of answers"),
+            );
+        }
+
+        if let Some(entry_match) = question.correct_entry_match() {
+            let pointer = format!("{pointer}/correctEntryMatch");
+            // `None` when the match is reported as not applying to the mode at all.
+            let allowed = match question.mode() {
+                QuestionMode::Select | QuestionMode::Order => {
+                    Some(!matches!(entry_match, EntryMatch::Pairs { .. }))
+                }
+                QuestionMode::Input => Some(matches!(
+                    entry_match,
+                    EntryMatch::Content { .. } | EntryMatch::Numeric { .. }
+                )),
+                QuestionMode::Match => Some(matches!(entry_match, EntryMatch::Pairs { .. })),
+                QuestionMode::Boolean => {
+                    self.error(
+                        pointer.clone(),
+                        "booleanAnswersNotAllowed",
+                        format!(
+                            "boolean question {question_id} takes `correct` instead of correctEntryMatch"
+                        ),
+                    );
+                    None
+                }
+                QuestionMode::Cloze | QuestionMode::Essay => {
+                    self.warning(
+                        pointer.clone(),
+                        "ignoredEntryMatch",
+                        format!(
+                            "question {question_id} is {:?} and ignores correctEntryMatch",
+                            question.mode()
+                        ),
+                    );
+                    None
+                }
+            };
+
+            match allowed {
+                Some(true) => {
+                    self.validate_entry_match(&pointer, entry_match, &answer_ids, &match_answer_ids)
+                }
+                Some(false) => self.error(
+                    pointer,
+                    "entryMatchModeMismatch",
+                    format!(
+                        "{:?} question {question_id} can not be judged by this correctEntryMatch",
+                        question.mode()
+                    ),
+                ),
+                None => {}
+            }
+        }
+
+        if let (Some(min), Some(max)) = (question.min_entries(), question.max_entries()) {
+            if min > max {
+                self.error(
+                    format!("{pointer}/minEntries"),
+                    "entryLimitsConflict",
+                    format!("minEntries {min} exceeds maxEntries {max}"),
+                );
+            }
+        }
+
+        if let Some(min_correct_entries) = question.min_correct_entries() {
+            let correct_entries = match (question.mode(), question.correct_entry_match()) {
+                (QuestionMode::Cloze, _) => question.blanks().as_ref().map_or(0, Vec::len),
+                (_, Some(entry_match)) => entry_match_len(entry_match),
+                (_, None) => 0,
+            };
+
+            if *min_correct_entries > correct_entries {
+                self.error(
+                    format!("{pointer}/minCorrectEntries"),
+                    "unreachableMinCorrectEntries",
+                    format!(
+                        "minCorrectEntries {min_correct_entries} exceeds the {correct_entries} correct entries"
+                    ),
+                );
+            }
+        }
+
+        if question.mode() == &QuestionMode::Cloze {
+            self.validate_blanks(pointer, question);
+        }
+    }
+
+    fn validate_blanks(&mut self, pointer: &str, question: &QuestionInput) {
+        let blanks = question.blanks().as_deref().unwrap_or_default();
+        let placeholders = placeholder_ids(question.content());
+        let mut blank_ids = HashSet::new();
+
+        if blanks.is_empty() {
+            self.error(
+                format!("{pointer}/blanks"),
+                "missingBlanks",
+                format!("cloze question {} has no blanks", question.id()),
+            );
+        }
+
+        for (index, blank) in blanks.iter().enumerate() {
+            let pointer = format!("{pointer}/blanks/{index}");
+
+            if !blank_ids.insert(*blank.id()) {
+                self.error(
+                    format!("{pointer}/id"),
+                    "duplicateBlankId",
+                    format!("blank {} is defined more than once", blank.id()),
+                );
+            }
+
+            if !placeholders.contains(blank.id()) {
+                self.error(
+                    pointer.clone(),
+                    "placeholderNotFound",
+                    format!("blank {} has no placeholder in the content", blank.id()),
+                );
+            }
+
+            let answer_ids = self.unique_answer_ids(&format!("{pointer}/answers"), blank.answers());
+
+            if let Some(entry_match) = blank.correct_entry_match() {
+                self.validate_entry_match(
+                    &format!("{pointer}/correctEntryMatch"),
+                    entry_match,
+                    &answer_ids,
+                    &HashSet::new(),
+                );
+            }
+        }
+
+        for blank_id in placeholders {
+            if !blank_ids.contains(&blank_id) {
+                self.error(
+                    format!("{pointer}/content"),
+                    "blankNotFound",
+                    format!("placeholder {{{{{blank_id}}}}} has no blank"),
+                );
+            }
+        }
+    }
+
+    fn unique_answer_ids(
+        &mut self,
+        pointer: &str,
+        answers: &Option<Vec<AnswerInput>>,
+    ) -> HashSet<usize> {
+        let mut answer_ids = HashSet::new();
+
+        for (index, answer) in answers.iter().flatten().enumerate() {
+            if !answer_ids.insert(*answer.id()) {
+                self.error(
+                    format!("{pointer}/{index}/id"),
+                    "duplicateAnswerId",
+                    format!("answer {} is defined more than once", answer.id()),
+                );
+            }
+        }
+
+        answer_ids
+    }
+
+    fn validate_entry_match(
+        &mut self,
+        pointer: &str,
+        entry_match: &EntryMatch,
+        answer_ids: &HashSet<usize>,
+        match_answer_ids: &HashSet<usize>,
+    ) {
+        match entry_match {
+            EntryMatch::Id { id } => {
+                for (index, answer_id) in id.iter().enumerate() {
+                    if !answer_ids.contains(answer_id) {
+                        self.error(
+                            format!("{pointer}/id/{index}"),
+                            "answerNotFound",
+                            format!("answer {answer_id} does not exist"),
+                        );
+                    }
+                }
+            }
+            EntryMatch::Content { content } => {
+                for (index, pattern) in content.iter().enumerate() {
+                    if let Err(error) = Regex::new(&format!("(?i){}", pattern)) {
+                        self.error(
+                            format!("{pointer}/content/{index}"),
+                            "invalidPattern",
+                            error.to_string(),
+                        );
+                    }
+                }
+            }
+            EntryMatch::Pairs { pairs } => {
+                for (index, (answer_id, match_answer_id)) in pairs.iter().enumerate() {
+                    if !answer_ids.contains(answer_id)
+                        || !match_answer_ids.contains(match_answer_id)
+                    {
+                        self.error(
+                            format!("{pointer}/pairs/{index}"),
+                            "pairAnswerNotFound",
+                            format!(
+                                "pair ({answer_id}, {match_answer_id}) references unknown answers"
+                            ),
+                        );
+                    }
+                }
+            }
+            EntryMatch::Numeric { numeric } => {
+                for (index, numeric_match) in numeric.iter().enumerate() {
+                    if let (Some(min), Some(max)) = (numeric_match.min(), numeric_match.max()) {
+                        if min > max {
+                            self.error(
+                                format!("{pointer}/numeric/{index}/min"),
+                                "numericRangeConflict",
+                                format!("min {min} exceeds max {max}"),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Branch rules are evaluated after `from_question_id`, so their jumps have to land after it.
+    fn validate_branches(
+        &mut self,
+        pointer: &str,
+        branches: &[BranchRuleInput],
+        from_question_id: Option<usize>,
+    ) {
+        for (index, rule) in branches.iter().enumerate() {
+            let pointer = format!("{pointer}/branches/{index}");
+            let question_id = rule.when().question_id();
+
+            if !self.question_ids.contains(question_id) {
+                self.error(
+                    format!("{pointer}/when/questionId"),
+                    "branchTargetNotFound",
+                    format!("branch depends on unknown question {question_id}"),
+                );
+            }
+
+            for (field, action) in [("then", rule.then()), ("else", rule.otherwise())] {
+                if let Some(action) = action {
+                    let pointer = format!("{pointer}/{field}");
+                    self.validate_branch_action(&pointer, action, from_question_id);
+                }
+            }
+        }
+    }
+
+    fn validate_branch_action(
+        &mut self,
+        pointer: &str,
+        action: &BranchActionInput,
+        from_question_id: Option<usize>,
+    ) {
+        let question_targets = action
+            .go_to_question()
+            .iter()
+            .map(|question_id| ("goToQuestion".to_string(), question_id))
+            .chain(
+                action
+                    .skip_questions()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, question_id)| (format!("skipQuestions/{index}"), question_id)),
+            );
+
+        for (field, question_id) in question_targets {
+            if !self.question_ids.contains(question_id) {
+                self.error(
+                    format!("{pointer}/{field}"),
+                    "branchTargetNotFound",
+                    format!("branch targets unknown question {question_id}"),
+                );
+            }
+        }
+
+        let section_targets = action
+            .go_to_section()
+            .iter()
+            .map(|section_id| ("goToSection".to_string(), section_id))
+            .chain(
+                action
+                    .skip_sections()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, section_id)| (format!("skipSections/{index}"), section_id)),
+            );
+
+        for (field, section_id) in section_targets {
+            if !self.section_ids.contains(section_id) {
+                self.error(
+                    format!("{pointer}/{field}"),
+                    "branchTargetNotFound",
+                    format!("branch targets unknown section {section_id}"),
+                );
+            }
+        }
+
+        if let Some(question_id) = action.go_to_question() {
+            if self.is_backward(*question_id, from_question_id) {
+                self.error(
+                    format!("{pointer}/goToQuestion"),
+                    "backwardBranchTarget",
+                    format!("branch goes back to question {question_id}"),
+                );
+            }
+        }
+
+        if let Some(section_id) = action.go_to_section() {
+            let first_question_id = self
+                .section_question_ids
+                .get(section_id)
+                .and_then(|question_ids| question_ids.first().copied());
+
+            if let Some(first_question_id) = first_question_id {
+                if self.is_backward(first_question_id, from_question_id) {
+                    self.error(
+                        format!("{pointer}/goToSection"),
+                        "backwardBranchTarget",
+                        format!("branch goes back to section {section_id}"),
+                    );
+                }
+            }
+        }
+    }
+
+    fn is_backward(&self, question_id: usize, from_question_id: Option<usize>) -> bool {
+        let position = |question_id| self.question_order.iter().position(|id| *id == question_id);
+
+        match (position(question_id), from_question_id.and_then(position)) {
+            (Some(index), Some(from_index)) => index <= from_index,
+            _ => false,
+        }
+    }
+}
+
+fn entry_match_len(entry_match: &EntryMatch) -> usize {
+    match entry_match {
+        EntryMatch::Id { id } => id.len(),
+        EntryMatch::Content { content } => content.len(),
+        EntryMatch::Pairs { pairs } => pairs.len(),
+        EntryMatch::Numeric { numeric } => numeric.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<(&str, &'static str)> {
+        diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.pointer().as_str(), diagnostic.code()))
+            .collect()
+    }
+
+    #[test]
+    fn it_accepts_valid_quizzes() {
+        let input_json = include_str!("../tests/input/open_exam_quiz.json");
+        assert!(codes(&validate(input_json)).is_empty());
+    }
+
+    #[test]
+    fn it_collects_every_problem_with_its_location() {
+        let input_json = r#"{
+            "uid": "invalid", "version": 1, "mode": "open",
+            "minAnsweredQuestions": 4, "passScore": 10,
+            "sections": [{
+                "id": 1,
+                "questions": [
+                    {
+                        "id": 1, "content": "Pick", "mode": "select",
+                        "minEntries": 3, "maxEntries": 1, "minCorrectEntries": 2,
+                        "answers": [{ "id": 1, "content": "A" }, { "id": 1, "content": "B" }],
+                        "correctEntryMatch": { "id": [1, 7] }
+                    },
+                    {
+                        "id": 1, "content": "Type", "mode": "input",
+                        "correctEntryMatch": { "id": [1] },
+                        "branches": [{ "when": { "questionId": 9 }, "then": { "skipSections": [2] } }]
+                    },
+                    { "id": 3, "content": "Pick", "mode": "select" }
+                ]
+            }]
+        }"#;
+
+        assert_eq!(
+            codes(&validate(input_json)),
+            vec![
+                ("/sections/0/questions/0/answers/1/id", "duplicateAnswerId"),
+                (
+                    "/sections/0/questions/0/correctEntryMatch/id/1",
+                    "answerNotFound"
+                ),
+                ("/sections/0/questions/0/minEntries", "entryLimitsConflict"),
+                ("/sections/0/questions/1/id", "duplicateQuestionId"),
+                (
+                    "/sections/0/questions/1/correctEntryMatch",
+                    "entryMatchModeMismatch"
+                ),
+                ("/sections/0/questions/2/answers", "missingAnswers"),
+                (
+                    "/sections/0/questions/1/branches/0/when/questionId",
+                    "branchTargetNotFound"
+                ),
+                (
+                    "/sections/0/questions/1/branches/0/then/skipSections/0",
+                    "branchTargetNotFound"
+                ),
+                ("/minAnsweredQuestions", "unreachableThreshold"),
+                ("/passScore", "unreachableThreshold"),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_rejects_branches_that_go_backward() {
+        let input_json = include_str!("../tests/input/branching_quiz.json").replace(
+            "\"else\": { \"skipSections\": [3] }",
+            "\"else\": { \"goToQuestion\": 1 }",
+        );
+
+        assert_eq!(
+            codes(&validate(&input_json)),
+            vec![(
+                "/sections/0/questions/0/branches/0/else/goToQuestion",
+                "backwardBranchTarget"
+            )]
+        );
+    }
+
+    #[test]
+    fn it_only_warns_about_entry_matches_that_are_ignored() {
+        let input_json = include_str!("../tests/input/essay_quiz.json").replace(
+            "\"minEntries\": 1,",
+            "\"minEntries\": 1, \"correctEntryMatch\": { \"id\": [7] },",
+        );
+        let diagnostics = validate(&input_json);
+
+        assert_eq!(
+            codes(&diagnostics),
+            vec![(
+                "/sections/0/questions/1/correctEntryMatch",
+                "ignoredEntryMatch"
+            )]
+        );
+        assert_eq!(*diagnostics[0].severity(), Severity::Warning);
+    }
+
+    #[test]
+    fn it_reports_malformed_json() {
+        let diagnostics = validate("{");
+        assert_eq!(codes(&diagnostics), vec![("", "invalidJson")]);
+        assert_eq!(*diagnostics[0].severity(), Severity::Error);
+    }
+}
@@ -1,8 +1,9 @@
-use crate::input::{QuestionMode, QuizMode};
+use crate::input::{FeedbackInput, FeedbackReveal, QuestionMode, QuizMode, ScoringPolicy};
 use crate::state::{
-    AnswerStateStatus, QuestionState, QuestionStateStatus, QuizState, QuizStateStatus,
+    AnswerState, AnswerStateStatus, AttemptResult, QuestionAvailability, QuestionState,
+    QuestionStateStatus, QuizState, QuizStateStatus, ReviewState, SectionStateStatus,
 };
-use crate::store::{QuestionStore, SectionStore};
+use crate::store::{AnswerStore, BlankStore, QuestionStore, SectionStore};
 use derive_getters::Getters;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -36,6 +37,7 @@ pub enum QuestionViewStatus {
     Answered,
     AnsweredCorrectly,
     AnsweredWrongly,
+    AwaitingReview,
 }
 
 impl From<&QuestionStateStatus> for QuestionViewStatus {
@@ -45,6 +47,25 @@ impl From<&QuestionStateStatus> for QuestionViewStatus {
             QuestionStateStatus::Answered => QuestionViewStatus::Answered,
             QuestionStateStatus::AnsweredCorrectly => QuestionViewStatus::AnsweredCorrectly,
             QuestionStateStatus::AnsweredWrongly => QuestionViewStatus::AnsweredWrongly,
+            QuestionStateStatus::AwaitingReview => QuestionViewStatus::AwaitingReview,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum QuestionAvailabilityView {
+    Reachable,
+    Skipped,
+    Locked,
+}
+
+impl From<QuestionAvailability> for QuestionAvailabilityView {
+    fn from(item: QuestionAvailability) -> Self {
+        match item {
+            QuestionAvailability::Reachable => Self::Reachable,
+            QuestionAvailability::Skipped => Self::Skipped,
+            QuestionAvailability::Locked => Self::Locked,
         }
     }
 }
@@ -53,8 +74,106 @@ impl From<&QuestionStateStatus> for QuestionViewStatus {
 #[serde(rename_all = "camelCase")]
 pub struct AnswerView {
     id: Option<usize>,
+    match_id: Option<usize>,
+    blank_id: Option<usize>,
     content: String,
     status: AnswerViewStatus,
+    feedback: Option<String>,
+}
+
+impl From<&AnswerStore> for AnswerView {
+    fn from(answer_store: &AnswerStore) -> Self {
+        Self {
+            id: Some(*answer_store.id()),
+            match_id: None,
+            blank_id: None,
+            content: answer_store.content().clone(),
+            status: AnswerViewStatus::Pending,
+            feedback: None,
+        }
+    }
+}
+
+impl From<&AnswerState> for AnswerView {
+    fn from(answer_state: &AnswerState) -> Self {
+        Self {
+            id: *answer_state.id(),
+            match_id: *answer_state.match_id(),
+            blank_id: *answer_state.blank_id(),
+            content: answer_state.content().clone(),
+            status: answer_state.status().into(),
+            feedback: None,
+        }
+    }
+}
+
+impl AnswerView {
+    fn reveal_feedback(&mut self, answer_store: &AnswerStore) {
+        self.feedback = match self.status {
+            AnswerViewStatus::Pending => None,
+            AnswerViewStatus::Answered => feedback_text(answer_store.feedback(), None),
+            AnswerViewStatus::AnsweredCorrectly(_) => {
+                feedback_text(answer_store.feedback(), Some(true))
+            }
+            AnswerViewStatus::AnsweredWrongly => {
+                feedback_text(answer_store.feedback(), Some(false))
+            }
+        };
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Getters, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewView {
+    points: f64,
+    feedback: Option<String>,
+    grader: String,
+}
+
+impl From<&ReviewState> for ReviewView {
+    fn from(review_state: &ReviewState) -> Self {
+        Self {
+            points: *review_state.points(),
+            feedback: review_state.feedback().clone(),
+            grader: review_state.grader().clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Getters, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlankView {
+    id: usize,
+    answers: Vec<AnswerView>,
+}
+
+impl BlankView {
+    pub fn new(blank_store: &BlankStore, question_state: Option<&QuestionState>) -> Self {
+        let selected_answer = question_state.and_then(|question_state| {
+            question_state
+                .answer_states()
+                .iter()
+                .find(|answer_state| answer_state.blank_id() == &Some(*blank_store.id()))
+        });
+
+        Self {
+            id: *blank_store.id(),
+            answers: blank_store
+                .answer_ids()
+                .iter()
+                .filter_map(|answer_id| blank_store.answers().get(answer_id))
+                .map(|answer_store| match selected_answer {
+                    Some(answer_state) if answer_state.id() == &Some(*answer_store.id()) => {
+                        AnswerView {
+                            status: answer_state.status().into(),
+                            ..AnswerView::from(answer_store)
+                        }
+                    }
+                    _ => AnswerView::from(answer_store),
+                })
+                .collect(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Getters, Clone)]
@@ -62,44 +181,107 @@ pub struct AnswerView {
 pub struct QuestionView {
     id: usize,
     status: QuestionViewStatus,
+    availability: QuestionAvailabilityView,
+    remaining_time: Option<u64>,
     title: Option<String>,
     content: String,
     mode: QuestionMode,
     min_entries: Option<usize>,
     max_entries: Option<usize>,
+    scoring_policy: ScoringPolicy,
+    points: f64,
+    credit: f64,
+    penalty: f64,
+    score: f64,
     answers: Vec<AnswerView>,
+    match_answers: Vec<AnswerView>,
+    blanks: Vec<BlankView>,
+    review: Option<ReviewView>,
+    feedback: Option<String>,
+    explanation: Option<String>,
+    hints: Vec<String>,
+    hints_count: usize,
+    hint_cost: f64,
+    assisted: bool,
+}
+
+fn feedback_text(feedback: &Option<FeedbackInput>, correct: Option<bool>) -> Option<String> {
+    feedback.as_ref().and_then(|feedback| match correct {
+        Some(true) => feedback.correct().clone(),
+        Some(false) => feedback.wrong().clone(),
+        None => feedback.answered().clone(),
+    })
 }
 
 impl QuestionView {
-    pub fn new(question_store: &QuestionStore, question_state: Option<&QuestionState>) -> Self {
+    /// Builds the view of a question. `quiz_status` is passed in rather than evaluated here so
+    /// that views of whole sections and quizzes evaluate it once.
+    pub fn new(
+        question_store: &QuestionStore,
+        quiz_state: &QuizState,
+        quiz_status: &QuizStateStatus,
+    ) -> Self {
+        let question_state = quiz_state.question_state().get(question_store.id());
+
         let status = match question_state {
             Some(question_state) => question_state.status().into(),
             None => QuestionViewStatus::Pending,
         };
 
-        Self {
+        let revealed = match quiz_state.store().feedback_reveal() {
+            FeedbackReveal::OnStatus => matches!(
+                status,
+                QuestionViewStatus::Answered
+                    | QuestionViewStatus::AnsweredCorrectly
+                    | QuestionViewStatus::AnsweredWrongly
+            ),
+            FeedbackReveal::OnQuizFinished => matches!(
+                quiz_status,
+                QuizStateStatus::Submitted | QuizStateStatus::Completed | QuizStateStatus::Failed
+            ),
+        };
+
+        let revealed_hints = match question_state {
+            Some(question_state) => *question_state.revealed_hints(),
+            None => 0,
+        };
+
+        let mut view = Self {
             id: question_store.id().clone(),
             status,
+            availability: quiz_state
+                .question_availability(*question_store.id())
+                .into(),
+            remaining_time: quiz_state.question_remaining_time(*question_store.id()),
             title: question_store.title().clone(),
             content: question_store.content().clone(),
             mode: question_store.mode().clone(),
             min_entries: question_store.min_entries().clone(),
             max_entries: question_store.max_entries().clone(),
+            scoring_policy: question_store.scoring_policy().clone(),
+            points: question_store.max_points(),
+            credit: match question_state {
+                Some(question_state) => *question_state.credit(),
+                None => 0.0,
+            },
+            penalty: match question_state {
+                Some(question_state) => *question_state.penalty(),
+                None => 0.0,
+            },
+            score: match question_state {
+                Some(question_state) => *question_state.score(),
+                None => 0.0,
+            },
             answers: match question_store.mode() {
-                QuestionMode::Select => {
+                QuestionMode::Select | QuestionMode::Match | QuestionMode::Boolean => {
                     let mut views: HashMap<usize, AnswerView> = question_store
                         .answer_ids()
                         .iter()
-                        .filter_map(|answer_id| match question_store.answers().get(answer_id) {
-                            Some(answer_store) => Some((
-                                *answer_id,
-                                AnswerView {
-                                    id: Some(answer_id.clone()),
-                                    content: answer_store.content().clone(),
-                                    status: AnswerViewStatus::Pending,
-                                },
-                            )),
-                            None => None,
+                        .filter_map(|answer_id| {
+                            question_store
+                                .answers()
+                                .get(answer_id)
+                                .map(|answer_store| (*answer_id, AnswerView::from(answer_store)))
                         })
                         .collect();
 
@@ -107,6 +289,7 @@ impl QuestionView {
                         for answer_state in question_state.answer_states() {
                             if let Some(answer_id) = answer_state.id() {
                                 if let Some(view) = views.get_mut(answer_id) {
+                                    view.match_id = *answer_state.match_id();
                                     view.status = answer_state.status().into();
                                 }
                             }
@@ -119,20 +302,153 @@ impl QuestionView {
                         .filter_map(|answer_id| views.remove(answer_id))
                         .collect()
                 }
-                QuestionMode::Input => match question_state {
+                QuestionMode::Order => match question_state {
                     Some(question_state) => question_state
                         .answer_states()
                         .iter()
-                        .map(|answer_state| AnswerView {
-                            id: None,
-                            content: answer_state.content().clone(),
-                            status: answer_state.status().into(),
-                        })
+                        .map(AnswerView::from)
+                        .collect(),
+                    None => question_store
+                        .answer_ids()
+                        .iter()
+                        .filter_map(|answer_id| question_store.answers().get(answer_id))
+                        .map(AnswerView::from)
+                        .collect(),
+                },
+                QuestionMode::Input | QuestionMode::Essay => match question_state {
+                    Some(question_state) => question_state
+                        .answer_states()
+                        .iter()
+                        .map(AnswerView::from)
                         .collect(),
                     None => Vec::new(),
                 },
+                QuestionMode::Cloze => question_store
+                    .blank_ids()
+                    .iter()
+                    .map(|blank_id| {
+                        match question_state.and_then(|question_state| {
+                            question_state
+                                .answer_states()
+                                .iter()
+                                .find(|answer_state| answer_state.blank_id() == &Some(*blank_id))
+                        }) {
+                            Some(answer_state) => AnswerView::from(answer_state),
+                            None => AnswerView {
+                                id: None,
+                                match_id: None,
+                                blank_id: Some(*blank_id),
+                                content: String::new(),
+                                status: AnswerViewStatus::Pending,
+                                feedback: None,
+                            },
+                        }
+                    })
+                    .collect(),
             },
+            match_answers: question_store
+                .match_answer_ids()
+                .iter()
+                .filter_map(|match_answer_id| question_store.match_answers().get(match_answer_id))
+                .map(|answer_store| AnswerView {
+                    status: question_state
+                        .and_then(|question_state| {
+                            question_state.answer_states().iter().find(|answer_state| {
+                                answer_state.match_id() == &Some(*answer_store.id())
+                            })
+                        })
+                        .map(|answer_state| answer_state.status().into())
+                        .unwrap_or(AnswerViewStatus::Pending),
+                    ..AnswerView::from(answer_store)
+                })
+                .collect(),
+            blanks: question_store
+                .blank_ids()
+                .iter()
+                .filter_map(|blank_id| question_store.blanks().get(blank_id))
+                .map(|blank_store| BlankView::new(blank_store, question_state))
+                .collect(),
+            review: question_state
+                .and_then(|question_state| question_state.review().as_ref())
+                .map(ReviewView::from),
+            feedback: None,
+            explanation: None,
+            hints: question_store
+                .hints()
+                .iter()
+                .take(revealed_hints)
+                .map(|hint| hint.content().clone())
+                .collect(),
+            hints_count: question_store.hints().len(),
+            hint_cost: match question_state {
+                Some(question_state) => *question_state.hint_cost(),
+                None => 0.0,
+            },
+            assisted: revealed_hints > 0,
+        };
+
+        if revealed {
+            view.reveal_feedback(question_store);
         }
+
+        view
+    }
+
+    fn reveal_feedback(&mut self, question_store: &QuestionStore) {
+        self.feedback = match self.status {
+            QuestionViewStatus::AnsweredCorrectly => {
+                feedback_text(question_store.feedback(), Some(true))
+            }
+            QuestionViewStatus::AnsweredWrongly => {
+                feedback_text(question_store.feedback(), Some(false))
+            }
+            QuestionViewStatus::Answered => feedback_text(question_store.feedback(), None),
+            _ => None,
+        };
+        self.explanation = question_store.explanation().clone();
+
+        for answer in self.answers.iter_mut() {
+            let answer_store = match (answer.id, answer.blank_id) {
+                (Some(answer_id), Some(blank_id)) => question_store
+                    .blanks()
+                    .get(&blank_id)
+                    .and_then(|blank_store| blank_store.answers().get(&answer_id)),
+                (Some(answer_id), None) => question_store.answers().get(&answer_id),
+                (None, _) => None,
+            };
+
+            if let Some(answer_store) = answer_store {
+                answer.reveal_feedback(answer_store);
+            }
+        }
+
+        for answer in self.match_answers.iter_mut() {
+            if let Some(answer_store) = answer
+                .id
+                .and_then(|answer_id| question_store.match_answers().get(&answer_id))
+            {
+                answer.reveal_feedback(answer_store);
+            }
+        }
+
+        for blank in self.blanks.iter_mut() {
+            let Some(blank_store) = question_store.blanks().get(&blank.id) else {
+                continue;
+            };
+
+            for answer in blank.answers.iter_mut() {
+                if let Some(answer_store) = answer
+                    .id
+                    .and_then(|answer_id| blank_store.answers().get(&answer_id))
+                {
+                    answer.reveal_feedback(answer_store);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn refresh_remaining_time(&mut self, quiz_state: &QuizState) {
+        self.remaining_time = quiz_state.question_remaining_time(self.id);
     }
 
     pub fn correct_answer_match_indexes(&self) -> Vec<usize> {
@@ -146,40 +462,99 @@ impl QuestionView {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum SectionViewStatus {
+    InProgress,
+    AwaitingReview,
+    Completed,
+    Failed,
+    Skipped,
+}
+
+impl From<SectionStateStatus> for SectionViewStatus {
+    fn from(item: SectionStateStatus) -> Self {
+        match item {
+            SectionStateStatus::InProgress => Self::InProgress,
+            SectionStateStatus::AwaitingReview => Self::AwaitingReview,
+            SectionStateStatus::Completed => Self::Completed,
+            SectionStateStatus::Failed => Self::Failed,
+            SectionStateStatus::Skipped => Self::Skipped,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Getters, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SectionView {
     id: usize,
     title: Option<String>,
     description: Option<String>,
+    mode: Option<QuizMode>,
+    status: SectionViewStatus,
+    remaining_time: Option<u64>,
+    answered_questions_count: usize,
+    correct_questions_count: usize,
+    wrong_questions_count: usize,
+    score: f64,
+    max_score: f64,
     questions: Vec<QuestionView>,
 }
 
 impl SectionView {
-    pub fn new(section_store: &SectionStore, quiz_state: &QuizState) -> Self {
+    pub fn new(
+        section_store: &SectionStore,
+        quiz_state: &QuizState,
+        quiz_status: &QuizStateStatus,
+    ) -> Self {
+        let progress = quiz_state
+            .section_progress(*section_store.id())
+            .unwrap_or_default();
+
         SectionView {
             id: section_store.id().clone(),
             title: section_store.title().clone(),
             description: section_store.description().clone(),
+            mode: section_store.mode().clone(),
+            status: match quiz_state.section_status(*section_store.id()) {
+                Ok(status) => status.into(),
+                Err(_) => SectionViewStatus::InProgress,
+            },
+            remaining_time: quiz_state.section_remaining_time(*section_store.id()),
+            answered_questions_count: *progress.answered_questions_count(),
+            correct_questions_count: *progress.correct_questions_count(),
+            wrong_questions_count: *progress.wrong_questions_count(),
+            score: *progress.score(),
+            max_score: *progress.max_score(),
             questions: section_store
                 .question_ids()
                 .iter()
                 .filter_map(|id| match quiz_state.store().questions().get(id) {
-                    Some(question_store) => Some(QuestionView::new(
-                        question_store,
-                        quiz_state.question_state().get(id),
-                    )),
+                    Some(question_store) => {
+                        Some(QuestionView::new(question_store, quiz_state, quiz_status))
+                    }
                     None => None,
                 })
                 .collect(),
         }
     }
+
+    pub(crate) fn refresh_remaining_time(&mut self, quiz_state: &QuizState) {
+        self.remaining_time = quiz_state.section_remaining_time(self.id);
+
+        for question in self.questions.iter_mut() {
+            question.refresh_remaining_time(quiz_state);
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum QuizViewStatus {
+    NotStarted,
     InProgress,
+    Submitted,
+    AwaitingReview,
     Completed,
     Failed,
 }
@@ -187,13 +562,42 @@ pub enum QuizViewStatus {
 impl From<QuizStateStatus> for QuizViewStatus {
     fn from(item: QuizStateStatus) -> Self {
         match item {
+            QuizStateStatus::NotStarted => Self::NotStarted,
             QuizStateStatus::InProgress => Self::InProgress,
+            QuizStateStatus::Submitted => Self::Submitted,
+            QuizStateStatus::AwaitingReview => Self::AwaitingReview,
             QuizStateStatus::Completed => Self::Completed,
             QuizStateStatus::Failed => Self::Failed,
         }
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Getters, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AttemptView {
+    attempt: usize,
+    status: QuizViewStatus,
+    score: f64,
+    max_score: f64,
+    answered_questions_count: usize,
+    correct_questions_count: usize,
+    wrong_questions_count: usize,
+}
+
+impl From<AttemptResult> for AttemptView {
+    fn from(item: AttemptResult) -> Self {
+        Self {
+            attempt: *item.attempt(),
+            status: item.status().clone().into(),
+            score: *item.score(),
+            max_score: *item.max_score(),
+            answered_questions_count: *item.answered_questions_count(),
+            correct_questions_count: *item.correct_questions_count(),
+            wrong_questions_count: *item.wrong_questions_count(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Getters, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct QuizView {
@@ -203,15 +607,26 @@ pub struct QuizView {
     description: Option<String>,
     quiz_mode: QuizMode,
     status: QuizViewStatus,
+    remaining_time: Option<u64>,
     answered_questions_count: usize,
     correct_questions_count: usize,
     wrong_questions_count: usize,
+    awaiting_review_count: usize,
+    assisted_questions_count: usize,
+    score: f64,
+    max_score: f64,
+    penalty: f64,
+    attempt: usize,
+    max_attempts: Option<usize>,
+    attempts: Vec<AttemptView>,
+    final_score: Option<f64>,
     sections: Vec<SectionView>,
 }
 
 impl QuizView {
     pub fn new(quiz_state: &QuizState) -> Self {
         let quiz_store = quiz_state.store();
+        let quiz_status = quiz_state.quiz_status();
 
         QuizView {
             uid: quiz_store.uid().clone(),
@@ -219,18 +634,44 @@ impl QuizView {
             title: quiz_store.title().clone(),
             description: quiz_store.description().clone(),
             quiz_mode: quiz_store.quiz_mode().clone(),
-            status: quiz_state.quiz_status().into(),
+            status: quiz_status.clone().into(),
+            remaining_time: quiz_state.quiz_remaining_time(),
             answered_questions_count: quiz_state.answered_questions_count(),
             correct_questions_count: quiz_state.correct_questions_count(),
             wrong_questions_count: quiz_state.wrong_questions_count(),
+            awaiting_review_count: quiz_state.awaiting_review_count(),
+            assisted_questions_count: quiz_state.assisted_questions_count(),
+            score: quiz_state.score(),
+            max_score: quiz_store.max_score(),
+            penalty: quiz_state.penalty(),
+            attempt: *quiz_state.attempt(),
+            max_attempts: *quiz_store.max_attempts(),
+            attempts: quiz_state
+                .attempts()
+                .iter()
+                .cloned()
+                .chain(std::iter::once(quiz_state.attempt_result()))
+                .map(AttemptView::from)
+                .collect(),
+            final_score: quiz_state.final_score(),
             sections: quiz_store
                 .section_ids()
                 .iter()
                 .filter_map(|id| match quiz_state.store().sections().get(id) {
-                    Some(section_store) => Some(SectionView::new(section_store, quiz_state)),
+                    Some(section_store) => {
+                        Some(SectionView::new(section_store, quiz_state, &quiz_status))
+                    }
                     None => None,
                 })
                 .collect(),
         }
     }
+
+    pub(crate) fn refresh_remaining_time(&mut self, quiz_state: &QuizState) {
+        self.remaining_time = quiz_state.quiz_remaining_time();
+
+        for section in self.sections.iter_mut() {
+            section.refresh_remaining_time(quiz_state);
+        }
+    }
 }
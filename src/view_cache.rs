@@ -21,6 +21,12 @@ impl ViewCache {
         }
     }
 
+    pub fn clear(&mut self) {
+        self.quiz_view = None;
+        self.question_views.clear();
+        self.section_views.clear();
+    }
+
     pub fn cache_question(&mut self, generation: usize, question: QuestionView) -> QuestionView {
         self.question_views.insert(
             question.id().clone(),